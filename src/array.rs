@@ -0,0 +1,435 @@
+use crate::byte_reader::ByteReader;
+use crate::byte_writer::ByteWriter;
+use crate::error::{InError, Location, OutError};
+use crate::util;
+use std::io::{Read, Write};
+
+/// Programming language of the array literal
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Language {
+    /// g.e. 'unsigned char data[] = { 0x00, 0xff, };', followed by the 'data_len' variable
+    C,
+    /// g.e. 'const DATA: [u8; 2] = [ 0x00, 0xff, ];'
+    Rust,
+    /// g.e. 'data = bytes([ 0x00, 0xff, ])'
+    Python,
+    /// g.e. 'var data = []byte{ 0x00, 0xff, }'
+    Go,
+    /// g.e. 'byte[] data = { 0x00, (byte) 0xff, };', values greater than 127 are cast since
+    /// bytes are signed
+    Java,
+}
+
+impl Language {
+    /// Default identifier
+    pub fn name(self) -> &'static str {
+        match self {
+            Language::Rust => "DATA",
+            _ => "data",
+        }
+    }
+    fn header(self, name: &str, len: usize) -> String {
+        match self {
+            Language::C => format!("unsigned char {name}[] = {{\n"),
+            Language::Rust => format!("const {name}: [u8; {len}] = [\n"),
+            Language::Python => format!("{name} = bytes([\n"),
+            Language::Go => format!("var {name} = []byte{{\n"),
+            Language::Java => format!("byte[] {name} = {{\n"),
+        }
+    }
+    fn footer(self, name: &str, len: usize) -> String {
+        match self {
+            Language::C => format!("}};\nunsigned int {name}_len = {len};\n"),
+            Language::Rust => "];\n".to_string(),
+            Language::Python => "])\n".to_string(),
+            Language::Go => "}\n".to_string(),
+            Language::Java => "};\n".to_string(),
+        }
+    }
+    fn indent(self) -> &'static str {
+        match self {
+            Language::Go => "\t",
+            _ => "    ",
+        }
+    }
+    fn element(self, byte: u8) -> String {
+        match self {
+            Language::Java if byte > 0x7f => format!("(byte) 0x{byte:02x},"),
+            _ => format!("0x{byte:02x},"),
+        }
+    }
+}
+
+/// An iterator over Result<u8,[InError]>
+///
+/// Reads bytes from an array literal in any [Language]: elements are enclosed in the first '{' or
+/// '[' following the '=' (if any) and the matching closing bracket, while anything else is
+/// ignored; without brackets the whole input is read as a list of elements.
+///
+/// Elements are integer literals in the range (-128, 255) separated by commas and whitespace;
+/// they can be decimal, hexadecimal ('0x'), binary ('0b') or octal ('0o' or a leading '0'),
+/// optionally cast (g.e. '(byte) 0xff') or suffixed (g.e. '0xffu8'). Comments ('//', '/* */' and
+/// '#') are skipped.
+///
+/// Since the whole input is needed to find the array, input is buffered.
+///
+/// [InError]: crate::error::InError
+pub struct Reader<R: Read> {
+    in_bytes: util::Input<R>,
+    input: Option<Vec<u8>>,
+    index: usize,
+    close: Option<u8>,
+    ended: bool,
+}
+
+impl<R: Read> Reader<R> {
+    pub fn new(read: R) -> Self {
+        Reader {
+            in_bytes: util::Input::new(read),
+            input: None,
+            index: 0,
+            close: None,
+            ended: false,
+        }
+    }
+
+    /// Buffers the whole input and finds the beginning of the array
+    fn start(&mut self) -> Result<(), InError> {
+        let input = self
+            .in_bytes
+            .by_ref()
+            .collect::<std::io::Result<Vec<u8>>>()
+            .map_err(InError::StdIO)?;
+        let from = input.iter().position(|&c| c == b'=').map_or(0, |i| i + 1);
+        let mut i = from;
+        while i < input.len() {
+            let close = match input[i] {
+                b'[' => b']',
+                b'{' => b'}',
+                _ => {
+                    i += 1;
+                    continue;
+                }
+            };
+            // g.e. Go '[]byte'
+            if input[i + 1..].starts_with(b"]")
+                && input.get(i + 2).is_some_and(u8::is_ascii_alphabetic)
+            {
+                i += 2;
+                continue;
+            }
+            self.close = Some(close);
+            self.index = i + 1;
+            break;
+        }
+        if self.close.is_none() {
+            self.index = from;
+        }
+        self.input = Some(input);
+        Ok(())
+    }
+
+    /// Skips whitespace, separators and comments
+    fn skip(&mut self, input: &[u8]) {
+        while self.index < input.len() {
+            let rest = &input[self.index..];
+            if rest[0].is_ascii_whitespace() || rest[0] == b',' {
+                self.index += 1;
+            } else if rest.starts_with(b"//") || rest[0] == b'#' {
+                let len = rest.iter().position(|&c| c == b'\n').unwrap_or(rest.len());
+                self.index += len;
+            } else if rest.starts_with(b"/*") {
+                let len = rest
+                    .windows(2)
+                    .position(|w| w == b"*/")
+                    .map_or(rest.len(), |i| i + 2);
+                self.index += len;
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Parses next element
+    fn element(&mut self, input: &[u8]) -> Result<u8, InError> {
+        if input[self.index] == b'(' {
+            let len =
+                input[self.index..]
+                    .iter()
+                    .position(|&c| c == b')')
+                    .ok_or(InError::ShortIO {
+                        bytes: 0,
+                        expected: 1,
+                    })?;
+            self.index += len + 1;
+            self.skip(input);
+        }
+        let negative = input.get(self.index) == Some(&b'-');
+        if negative || input.get(self.index) == Some(&b'+') {
+            self.index += 1;
+        }
+        let len = input[self.index..]
+            .iter()
+            .position(|c| !c.is_ascii_alphanumeric() && *c != b'_')
+            .unwrap_or(input.len() - self.index);
+        if len == 0 {
+            let c = input.get(self.index).map_or(' ', |&c| c as char);
+            self.index += 1;
+            return Err(InError::InvalidByte(c));
+        }
+        let start = self.index;
+        self.index += len;
+        let token = input[start..self.index].to_ascii_lowercase();
+        let (radix, skip) = match token.as_slice() {
+            [b'0', b'x', ..] => (16, 2),
+            [b'0', b'b', ..] => (2, 2),
+            [b'0', b'o', ..] => (8, 2),
+            [b'0', b'0'..=b'9', ..] => (8, 1),
+            _ => (10, 0),
+        };
+        let mut digits = &token[skip..];
+        for suffix in [b"u8".as_slice(), b"i8", b"ul", b"u", b"l"] {
+            if digits.len() > suffix.len() && digits.ends_with(suffix) {
+                digits = &digits[..digits.len() - suffix.len()];
+                break;
+            }
+        }
+        let mut value = 0u32;
+        for (i, &d) in digits.iter().enumerate().filter(|(_, &d)| d != b'_') {
+            let Some(digit) = (d as char).to_digit(radix) else {
+                self.index = start + skip + i + 1;
+                return Err(InError::InvalidByte(input[start + skip + i] as char));
+            };
+            value = value.saturating_mul(radix).saturating_add(digit);
+        }
+        let byte = match (negative, value) {
+            (false, 0..=255) => value as u8,
+            (true, 0..=128) => (value as i32).wrapping_neg() as u8,
+            _ => return Err(InError::InvalidByte(input[self.index - 1] as char)),
+        };
+        Ok(byte)
+    }
+
+    fn next_element(&mut self, input: &[u8]) -> Option<Result<u8, InError>> {
+        self.skip(input);
+        match (input.get(self.index), self.close) {
+            (Some(&c), Some(close)) if c == close => {
+                self.index += 1;
+                None
+            }
+            (None, Some(_)) => Some(Err(InError::ShortIO {
+                bytes: 0,
+                expected: 1,
+            })),
+            (None, None) => None,
+            _ => Some(self.element(input)),
+        }
+    }
+}
+
+impl<R: Read> Iterator for Reader<R> {
+    type Item = Result<u8, InError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.ended {
+            return None;
+        }
+        if self.input.is_none() {
+            if let Err(e) = self.start() {
+                self.input = Some(vec![]);
+                self.ended = true;
+                return Some(Err(e));
+            }
+        }
+        let input = self.input.take().unwrap();
+        let element = self.next_element(&input);
+        self.input = Some(input);
+        match element {
+            Some(Err(InError::ShortIO { .. })) | None => self.ended = true,
+            _ => {}
+        }
+        element
+    }
+}
+
+impl<R: Read> ByteReader for Reader<R> {
+    fn location(&self) -> Location {
+        let Some(input) = &self.input else {
+            return self.in_bytes.location();
+        };
+        let read = &input[..self.index.min(input.len())];
+        let Some(last) = read.len().checked_sub(1) else {
+            return Location::default();
+        };
+        let line_start = read[..last]
+            .iter()
+            .rposition(|&c| c == b'\n')
+            .map_or(0, |i| i + 1);
+        Location {
+            offset: last,
+            line: 1 + read[..last].iter().filter(|&&c| c == b'\n').count(),
+            column: 1 + last - line_start,
+        }
+    }
+}
+
+/// Writes bytes to the output stream as an array literal in the provided [Language]
+///
+/// Elements are written in hexadecimal, a fixed number per line. Since the number of elements is
+/// needed in the declaration (g.e. for Rust), bytes are buffered and only written on [finish].
+///
+/// [finish]: crate::byte_writer::ByteWriter::finish
+pub struct Writer<W: Write> {
+    out_bytes: W,
+    language: Language,
+    name: String,
+    columns: usize,
+    bytes: Vec<u8>,
+}
+
+impl<W: Write> Writer<W> {
+    pub fn new(out_bytes: W, language: Language) -> Self {
+        Self::with_layout(out_bytes, language, language.name(), 12)
+    }
+
+    /// Builds a writer with the provided identifier and number of elements per line (columns)
+    pub fn with_layout(out_bytes: W, language: Language, name: &str, columns: usize) -> Self {
+        Writer {
+            out_bytes,
+            language,
+            name: name.to_string(),
+            columns: columns.max(1),
+            bytes: vec![],
+        }
+    }
+}
+
+impl<W: Write> ByteWriter for Writer<W> {
+    fn write(&mut self, byte: u8) -> Result<(), OutError> {
+        self.bytes.push(byte);
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), OutError> {
+        let mut string = self.language.header(&self.name, self.bytes.len());
+        for line in self.bytes.chunks(self.columns) {
+            let elements: Vec<String> = line.iter().map(|&b| self.language.element(b)).collect();
+            string.push_str(self.language.indent());
+            string.push_str(&elements.join(" "));
+            string.push('\n');
+        }
+        string.push_str(&self.language.footer(&self.name, self.bytes.len()));
+        self.bytes.clear();
+        util::write(&mut self.out_bytes, string.as_bytes(), string.len())?;
+        util::flush(&mut self.out_bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(language: Language, layout: Option<(&str, usize)>) -> String {
+        let mut output = vec![];
+        let mut writer = match layout {
+            Some((name, columns)) => Writer::with_layout(&mut output, language, name, columns),
+            None => Writer::new(&mut output, language),
+        };
+        for b in [0x00, 0x7f, 0x80, 0xff, 0x41] {
+            writer.write(b).unwrap();
+        }
+        writer.finish().unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    fn decode(input: &str) -> Vec<u8> {
+        Reader::new(input.as_bytes()).map(|b| b.unwrap()).collect()
+    }
+
+    #[test]
+    fn write() {
+        assert_eq!(
+            "unsigned char data[] = {\n    0x00, 0x7f, 0x80, 0xff, 0x41,\n};\nunsigned int data_len = 5;\n",
+            encode(Language::C, None)
+        );
+        assert_eq!(
+            "const BLOB: [u8; 5] = [\n    0x00, 0x7f,\n    0x80, 0xff,\n    0x41,\n];\n",
+            encode(Language::Rust, Some(("BLOB", 2)))
+        );
+        assert_eq!(
+            "data = bytes([\n    0x00, 0x7f, 0x80, 0xff, 0x41,\n])\n",
+            encode(Language::Python, None)
+        );
+        assert_eq!(
+            "var data = []byte{\n\t0x00, 0x7f, 0x80, 0xff, 0x41,\n}\n",
+            encode(Language::Go, None)
+        );
+        assert_eq!(
+            "byte[] data = {\n    0x00, 0x7f, (byte) 0x80, (byte) 0xff, 0x41,\n};\n",
+            encode(Language::Java, None)
+        );
+    }
+
+    #[test]
+    fn read() {
+        let expected = [0x00, 0x7f, 0x80, 0xff, 0x41];
+        for language in [
+            Language::C,
+            Language::Rust,
+            Language::Python,
+            Language::Go,
+            Language::Java,
+        ] {
+            let input = encode(language, Some(("x", 2)));
+            assert_eq!(expected.as_slice(), decode(&input), "{language:?}");
+        }
+        assert_eq!(
+            expected.as_slice(),
+            decode("byte[] b = {0, 127, -128, -1, 65 /* A */}")
+        );
+        assert_eq!(
+            expected.as_slice(),
+            decode("0x0,0o177, 0b1000_0000 // comment\n 0377, 0x41u8")
+        );
+        assert_eq!(b"".as_slice(), decode("static uint8_t empty[] = {};"));
+        assert_eq!(b"".as_slice(), decode("empty = bytes([])"));
+        assert_eq!(b"".as_slice(), decode(""));
+    }
+
+    #[test]
+    fn read_invalid() {
+        let mut reader = Reader::new("x = [\n  0x12, 0x1g ]".as_bytes());
+        assert_eq!(0x12, reader.next().unwrap().unwrap());
+        assert!(matches!(
+            reader.next(),
+            Some(Err(InError::InvalidByte('g')))
+        ));
+        let expected = Location {
+            offset: 17,
+            line: 2,
+            column: 12,
+        };
+        assert_eq!(expected, reader.location());
+        let mut reader = Reader::new("[256, -129]".as_bytes());
+        assert!(matches!(
+            reader.next(),
+            Some(Err(InError::InvalidByte('6')))
+        ));
+        assert!(matches!(
+            reader.next(),
+            Some(Err(InError::InvalidByte('9')))
+        ));
+        let mut reader = Reader::new("{'a', 2".as_bytes());
+        assert!(matches!(
+            reader.next(),
+            Some(Err(InError::InvalidByte('\'')))
+        ));
+        assert!(matches!(
+            reader.next(),
+            Some(Err(InError::InvalidByte('a')))
+        ));
+        reader.next().unwrap().unwrap_err();
+        assert_eq!(2, reader.next().unwrap().unwrap());
+        assert!(matches!(reader.next(), Some(Err(InError::ShortIO { .. }))));
+        assert!(reader.next().is_none());
+    }
+}
@@ -1,7 +1,8 @@
+use crate::byte_reader::ByteReader;
 use crate::byte_writer::ByteWriter;
-use crate::error::{InError, OutError};
+use crate::error::{InError, Location, OutError};
 use crate::util;
-use std::io::{Bytes, Read, Write};
+use std::io::{Read, Write};
 
 /// An iterator over Result<u8,[InError]>
 ///
@@ -10,13 +11,13 @@ use std::io::{Bytes, Read, Write};
 ///
 /// [InError]: crate::error::InError
 pub struct Reader<R: Read> {
-    in_bytes: Bytes<R>,
+    in_bytes: util::Input<R>,
 }
 
 impl<R: Read> Reader<R> {
     pub fn new(read: R) -> Self {
         Reader {
-            in_bytes: read.bytes(),
+            in_bytes: util::Input::new(read),
         }
     }
 }
@@ -37,28 +38,104 @@ impl<R: Read> Iterator for Reader<R> {
     }
 }
 
+impl<R: Read> ByteReader for Reader<R> {
+    fn location(&self) -> Location {
+        self.in_bytes.location()
+    }
+}
+
+/// Representation of control characters (0-31 and 127) and non-ASCII byte values (128-255) in
+/// ASCII output; printable characters are always written unchanged
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum Representation {
+    /// control characters are written unchanged, non-ASCII byte values are invalid
+    #[default]
+    Strict,
+    /// C-style escape sequences (g.e. '\n', '\x1b' or '\xff'); since printable characters are
+    /// unchanged, the output is not reversible if the input already contains such text
+    Escape,
+    /// caret notation, as `cat -v` (g.e. '^A' or '^?'), non-ASCII byte values prefixed with 'M-'
+    /// (g.e. 'M-^A' or 'M-a')
+    Caret,
+    /// Unicode Control Pictures (g.e. '␀' or '␡'), UTF-8 encoded; non-ASCII byte values are
+    /// written as the replacement character '�'
+    ControlPictures,
+    /// the provided character, UTF-8 encoded (g.e. '.')
+    Replace(char),
+}
+
+impl Representation {
+    /// Representation of non printable byte values
+    fn string(self, byte: u8) -> Option<String> {
+        let string = match self {
+            Representation::Strict => return None,
+            Representation::Escape => match byte {
+                0x07 => "\\a".to_string(),
+                0x08 => "\\b".to_string(),
+                b'\t' => "\\t".to_string(),
+                b'\n' => "\\n".to_string(),
+                0x0b => "\\v".to_string(),
+                0x0c => "\\f".to_string(),
+                b'\r' => "\\r".to_string(),
+                _ => format!("\\x{byte:02x}"),
+            },
+            Representation::Caret => {
+                let meta = if byte.is_ascii() { "" } else { "M-" };
+                match byte & 0x7f {
+                    0x7f => format!("{meta}^?"),
+                    c if c < 0x20 => format!("{meta}^{}", (c + 0x40) as char),
+                    c => format!("{meta}{}", c as char),
+                }
+            }
+            Representation::ControlPictures => match byte {
+                0x7f => '\u{2421}'.to_string(),
+                c if c < 0x20 => char::from_u32(0x2400 + c as u32).unwrap().to_string(),
+                _ => '\u{fffd}'.to_string(),
+            },
+            Representation::Replace(c) => c.to_string(),
+        };
+        Some(string)
+    }
+}
+
 /// Writes ASCII characters to the output stream
 ///
-/// Produced characters are valid ASCII;
-/// byte values provided for writing must be in the range (0,127)
+/// Printable characters are written unchanged, while control characters and non-ASCII byte values
+/// are written according to the provided [Representation]: with the default one, produced
+/// characters are valid ASCII and byte values provided for writing must be in the range (0,127)
 pub struct Writer<W: Write> {
     out_bytes: W,
+    representation: Representation,
 }
 
 impl<W: Write> Writer<W> {
     pub fn new(out_bytes: W) -> Self {
-        Writer { out_bytes }
+        Self::with_representation(out_bytes, Representation::default())
+    }
+
+    pub fn with_representation(out_bytes: W, representation: Representation) -> Self {
+        Writer {
+            out_bytes,
+            representation,
+        }
     }
 }
 
 impl<W: Write> ByteWriter for Writer<W> {
     fn write(&mut self, byte: u8) -> Result<(), OutError> {
-        if byte.is_ascii() {
-            util::write(&mut self.out_bytes, &[byte], 1)
-        } else {
-            Err(OutError::InvalidByte(byte))
+        if byte.is_ascii_graphic() || byte == b' ' {
+            return util::write(&mut self.out_bytes, &[byte], 1);
+        }
+        match self.representation.string(byte) {
+            Some(string) => util::write(&mut self.out_bytes, string.as_bytes(), string.len()),
+            None if byte.is_ascii() => util::write(&mut self.out_bytes, &[byte], 1),
+            None => Err(OutError::InvalidByte(byte)),
         }
     }
+
+    fn finish(&mut self) -> Result<(), OutError> {
+        util::flush(&mut self.out_bytes)
+    }
 }
 
 #[cfg(test)]
@@ -84,4 +161,37 @@ mod tests {
         writer.write(input).unwrap();
         assert_eq!(input, output[0]);
     }
+
+    fn represent(input: &[u8], representation: Representation) -> String {
+        let mut output = vec![];
+        let mut writer = Writer::with_representation(&mut output, representation);
+        for b in input {
+            writer.write(*b).unwrap();
+        }
+        writer.finish().unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn write_representation() {
+        let input = b"a \\\n\x1b\x7f\x81\xe1";
+        assert_eq!(
+            "a \\\\n\\x1b\\x7f\\x81\\xe1",
+            represent(input, Representation::Escape)
+        );
+        assert_eq!("a \\^J^[^?M-^AM-a", represent(input, Representation::Caret));
+        assert_eq!(
+            "a \\\u{240a}\u{241b}\u{2421}\u{fffd}\u{fffd}",
+            represent(input, Representation::ControlPictures)
+        );
+        assert_eq!("a \\.....", represent(input, Representation::Replace('.')));
+        let mut output = vec![];
+        let mut writer = Writer::new(&mut output);
+        writer.write(b'\n').unwrap();
+        assert!(matches!(
+            writer.write(0x80),
+            Err(OutError::InvalidByte(0x80))
+        ));
+        assert_eq!(b"\n".as_slice(), output);
+    }
 }
@@ -0,0 +1,363 @@
+use crate::byte_reader::ByteReader;
+use crate::byte_writer::ByteWriter;
+use crate::error::{InError, Location, OutError};
+use crate::util;
+use std::io::{Read, Write};
+
+const Z85: &[u8; 85] =
+    b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ.-:+=^!/*?&<>()[]{}@%$#";
+
+/// Base85 variant
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Variant {
+    /// Ascii85 (btoa, Adobe): characters in the range ('!', 'u'), 'z' codes 4 zero bytes
+    Ascii85,
+    /// [Z85](https://rfc.zeromq.org/spec/32/): ZeroMQ alphabet, the number of bytes must be a
+    /// multiple of 4
+    Z85,
+}
+
+impl Variant {
+    fn valid(&self, c: u8) -> Option<u8> {
+        match self {
+            Variant::Ascii85 => match c {
+                b'!'..=b'u' => Some(c - b'!'),
+                _ => None,
+            },
+            Variant::Z85 => Z85.iter().position(|&s| s == c).map(|d| d as u8),
+        }
+    }
+    fn to_char(self, d: u8) -> u8 {
+        match self {
+            Variant::Ascii85 => b'!' + d,
+            Variant::Z85 => Z85[d as usize],
+        }
+    }
+}
+
+/// An iterator over Result<u8,[InError]>
+///
+/// Reads bytes from the input stream in the provided base85 [Variant]: every 5 characters code 4
+/// bytes. For Ascii85 the last group can be partial, the 'z' character codes a group of 4 zero
+/// bytes and the input may be enclosed in Adobe '<~' and '~>' delimiters (anything following '~>'
+/// is ignored). Any whitespace character is allowed and skipped.
+///
+/// [InError]: crate::error::InError
+pub struct Reader<R: Read> {
+    in_bytes: util::Input<R>,
+    variant: Variant,
+    pending: Vec<u8>,
+    started: bool,
+    ended: bool,
+    decoded: [u8; 4],
+    available: usize,
+    consumed: usize,
+}
+
+impl<R: Read> Reader<R> {
+    pub fn new(read: R, variant: Variant) -> Self {
+        Reader {
+            in_bytes: util::Input::new(read),
+            variant,
+            pending: vec![],
+            started: false,
+            ended: false,
+            decoded: [0u8; 4],
+            available: 0,
+            consumed: 0,
+        }
+    }
+
+    fn next_non_whitespace(&mut self) -> Option<<util::Input<R> as Iterator>::Item> {
+        if let Some(c) = self.pending.pop() {
+            return Some(Ok(c));
+        }
+        loop {
+            let c = self.in_bytes.next()?;
+            match c {
+                Ok(c) => {
+                    if c.is_ascii_whitespace() {
+                        continue;
+                    } else {
+                        return Some(Ok(c));
+                    }
+                }
+                Err(e) => {
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+
+    /// Skips the opening '<~' delimiter, if any
+    fn start(&mut self) -> Result<(), InError> {
+        self.started = true;
+        if self.variant == Variant::Ascii85 {
+            match self.next_non_whitespace() {
+                Some(Ok(b'<')) => match self.next_non_whitespace() {
+                    Some(Ok(b'~')) => {}
+                    // '<' is a valid digit too
+                    Some(Ok(c)) => self.pending.extend([c, b'<']),
+                    Some(Err(e)) => return Err(InError::StdIO(e)),
+                    None => self.pending.push(b'<'),
+                },
+                Some(Ok(c)) => self.pending.push(c),
+                Some(Err(e)) => return Err(InError::StdIO(e)),
+                None => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Decodes next group (up to 5 characters) into self.decoded
+    fn decode_group(&mut self) -> Option<Result<(), InError>> {
+        let mut digits = [84u8; 5];
+        let mut len = 0usize;
+        while len < 5 && !self.ended {
+            let c = match self.next_non_whitespace() {
+                None => break,
+                Some(Ok(c)) => c,
+                Some(Err(e)) => return Some(Err(InError::StdIO(e))),
+            };
+            if self.variant == Variant::Ascii85 {
+                if c == b'~' {
+                    match self.next_non_whitespace() {
+                        Some(Ok(b'>')) => {
+                            self.ended = true;
+                            break;
+                        }
+                        Some(Err(e)) => return Some(Err(InError::StdIO(e))),
+                        _ => return Some(Err(InError::InvalidByte('~'))),
+                    }
+                }
+                if c == b'z' && len == 0 {
+                    self.decoded = [0u8; 4];
+                    self.available = 4;
+                    self.consumed = 0;
+                    return Some(Ok(()));
+                }
+            }
+            match self.variant.valid(c) {
+                Some(digit) => {
+                    digits[len] = digit;
+                    len += 1;
+                }
+                None => return Some(Err(InError::InvalidByte(c as char))),
+            }
+        }
+        if len == 0 {
+            return None;
+        }
+        if len == 1 || (len < 5 && self.variant == Variant::Z85) {
+            return Some(Err(InError::ShortIO {
+                bytes: len,
+                expected: 5,
+            }));
+        }
+        let value = digits.iter().fold(0u64, |v, &d| v * 85 + d as u64);
+        if value > u32::MAX as u64 {
+            return Some(Err(InError::InvalidByte(
+                self.variant.to_char(digits[len - 1]) as char,
+            )));
+        }
+        self.decoded = (value as u32).to_be_bytes();
+        self.available = len - 1;
+        self.consumed = 0;
+        Some(Ok(()))
+    }
+}
+
+impl<R: Read> Iterator for Reader<R> {
+    type Item = Result<u8, InError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.started {
+            if let Err(e) = self.start() {
+                return Some(Err(e));
+            }
+        }
+        if self.consumed == self.available {
+            if let Err(e) = self.decode_group()? {
+                return Some(Err(e));
+            }
+        }
+        self.consumed += 1;
+        Some(Ok(self.decoded[self.consumed - 1]))
+    }
+}
+
+impl<R: Read> ByteReader for Reader<R> {
+    fn location(&self) -> Location {
+        self.in_bytes.location()
+    }
+}
+
+/// Writes bytes to the output stream in the provided base85 [Variant]
+///
+/// Every 4 bytes are written as 5 characters (for Ascii85, 4 zero bytes are written as 'z'), so
+/// the last partial group is only written on [finish]; no delimiters are written. For Z85 the
+/// number of bytes written must be a multiple of 4.
+///
+/// [finish]: crate::byte_writer::ByteWriter::finish
+pub struct Writer<W: Write> {
+    out_bytes: W,
+    variant: Variant,
+    group: [u8; 4],
+    len: usize,
+}
+
+impl<W: Write> Writer<W> {
+    pub fn new(out_bytes: W, variant: Variant) -> Self {
+        Writer {
+            out_bytes,
+            variant,
+            group: [0u8; 4],
+            len: 0,
+        }
+    }
+
+    fn encode_group(&mut self) -> Result<(), OutError> {
+        let len = self.len;
+        let mut value = u32::from_be_bytes(self.group);
+        self.group = [0u8; 4];
+        self.len = 0;
+        if len == 4 && value == 0 && self.variant == Variant::Ascii85 {
+            return util::write(&mut self.out_bytes, b"z", 1);
+        }
+        let mut string = [0u8; 5];
+        for c in string.iter_mut().rev() {
+            *c = self.variant.to_char((value % 85) as u8);
+            value /= 85;
+        }
+        util::write(&mut self.out_bytes, &string[..len + 1], len + 1)
+    }
+}
+
+impl<W: Write> ByteWriter for Writer<W> {
+    fn write(&mut self, byte: u8) -> Result<(), OutError> {
+        self.group[self.len] = byte;
+        self.len += 1;
+        if self.len == 4 {
+            self.encode_group()
+        } else {
+            Ok(())
+        }
+    }
+
+    fn finish(&mut self) -> Result<(), OutError> {
+        if self.len > 0 {
+            if self.variant == Variant::Z85 {
+                return Err(OutError::ShortIO {
+                    bytes: self.len,
+                    expected: 4,
+                });
+            }
+            self.encode_group()?;
+        }
+        util::flush(&mut self.out_bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(input: &[u8], variant: Variant) -> Vec<u8> {
+        let mut output = vec![];
+        let mut writer = Writer::new(&mut output, variant);
+        for b in input {
+            writer.write(*b).unwrap();
+        }
+        writer.finish().unwrap();
+        output
+    }
+
+    fn decode(input: &[u8], variant: Variant) -> Vec<u8> {
+        Reader::new(input, variant).map(|b| b.unwrap()).collect()
+    }
+
+    #[test]
+    fn read() {
+        assert_eq!(b"Man ".as_slice(), decode(b"9jqo^", Variant::Ascii85));
+        assert_eq!(b"Man s".as_slice(), decode(b"9jqo^Er", Variant::Ascii85));
+        assert_eq!(
+            [0u8, 0, 0, 0, b'M'].as_slice(),
+            decode(b"<~z9`~>", Variant::Ascii85)
+        );
+        assert_eq!(
+            b"Man s".as_slice(),
+            decode(b"<~9jqo^\nEr~>trailing", Variant::Ascii85)
+        );
+        assert_eq!(b"".as_slice(), decode(b"<~~>", Variant::Ascii85));
+        assert_eq!(
+            [0x86u8, 0x4f, 0xd2, 0x6f, 0xb5, 0x59, 0xf7, 0x5b].as_slice(),
+            decode(b"HelloWorld", Variant::Z85)
+        );
+    }
+
+    #[test]
+    fn read_leading_lt() {
+        assert_eq!(b"The ".as_slice(), decode(b"<+ohc", Variant::Ascii85));
+        assert_eq!(b"The ".as_slice(), decode(b"<~<+ohc~>", Variant::Ascii85));
+    }
+
+    #[test]
+    fn read_invalid() {
+        let mut reader = Reader::new(b"9jqo^F".as_slice(), Variant::Ascii85);
+        for _ in 0..4 {
+            reader.next().unwrap().unwrap();
+        }
+        assert!(matches!(
+            reader.next(),
+            Some(Err(InError::ShortIO {
+                bytes: 1,
+                expected: 5
+            }))
+        ));
+        let mut reader = Reader::new(b"9jzo^".as_slice(), Variant::Ascii85);
+        assert!(matches!(
+            reader.next(),
+            Some(Err(InError::InvalidByte('z')))
+        ));
+        let mut reader = Reader::new(b"uuuuu".as_slice(), Variant::Ascii85);
+        assert!(matches!(
+            reader.next(),
+            Some(Err(InError::InvalidByte('u')))
+        ));
+        let mut reader = Reader::new(b"Hell".as_slice(), Variant::Z85);
+        assert!(matches!(
+            reader.next(),
+            Some(Err(InError::ShortIO {
+                bytes: 4,
+                expected: 5
+            }))
+        ));
+    }
+
+    #[test]
+    fn write() {
+        assert_eq!(b"9jqo^".as_slice(), encode(b"Man ", Variant::Ascii85));
+        assert_eq!(b"9jqo^Er".as_slice(), encode(b"Man s", Variant::Ascii85));
+        assert_eq!(
+            b"z9`".as_slice(),
+            encode(&[0, 0, 0, 0, b'M'], Variant::Ascii85)
+        );
+        assert_eq!(b"!!".as_slice(), encode(&[0], Variant::Ascii85));
+        assert_eq!(
+            b"HelloWorld".as_slice(),
+            encode(
+                &[0x86, 0x4f, 0xd2, 0x6f, 0xb5, 0x59, 0xf7, 0x5b],
+                Variant::Z85
+            )
+        );
+        let mut output = vec![];
+        let mut writer = Writer::new(&mut output, Variant::Z85);
+        writer.write(0).unwrap();
+        assert!(matches!(
+            writer.finish(),
+            Err(OutError::ShortIO {
+                bytes: 1,
+                expected: 4
+            })
+        ));
+    }
+}
@@ -1,8 +1,9 @@
+use crate::byte_reader::ByteReader;
 use crate::byte_writer::ByteWriter;
-use crate::error::{InError, OutError};
-use crate::util;
+use crate::error::{InError, Location, OutError};
+use crate::text::{Format, Formatter, Lenient, Scanner};
 use crate::util::literals::*;
-use std::io::{Bytes, Read, Write};
+use std::io::{Read, Write};
 
 struct Base {
     base: u8,
@@ -17,11 +18,11 @@ impl Base {
         }
     }
     fn valid(&self, n: char) -> Option<u8> {
-        let digit = if ('0'..='9').contains(&n) {
+        let digit = if n.is_ascii_digit() {
             n as u8 - b'0'
-        } else if ('a'..='z').contains(&n) {
+        } else if n.is_ascii_lowercase() {
             10u8 + (n as u8 - b'a')
-        } else if ('A'..='Z').contains(&n) {
+        } else if n.is_ascii_uppercase() {
             10u8 + (n as u8 - b'A')
         } else {
             36u8
@@ -50,35 +51,27 @@ impl Base {
 /// Reads bytes from the input stream in the expected numeric base format, that means allowed
 /// characters depend on the particular numeric base (in any case in the ranges ('0', '9'), ('a', 'z') or ('A', 'Z'); any whitespace character is allowed and skipped)
 ///
+/// In [Lenient] mode, separators and prefixes are allowed too.
+///
 /// [InError]: crate::error::InError
+/// [Lenient]: crate::text::Lenient
 pub struct Reader<R: Read> {
-    in_bytes: Bytes<R>,
+    scanner: Scanner<R>,
     base: Base,
 }
 
 impl<R: Read> Reader<R> {
     pub fn new(read: R, base: u8) -> Self {
         Reader {
-            in_bytes: read.bytes(),
+            scanner: Scanner::new(read, None),
             base: Base::new(base),
         }
     }
 
-    fn next_non_whitespace(&mut self) -> Option<<Bytes<R> as Iterator>::Item> {
-        loop {
-            let c = self.in_bytes.next()?;
-            match c {
-                Ok(c) => {
-                    if c.is_ascii_whitespace() {
-                        continue;
-                    } else {
-                        return Some(Ok(c));
-                    }
-                }
-                Err(e) => {
-                    return Some(Err(e));
-                }
-            }
+    pub fn lenient(read: R, base: u8, lenient: Lenient) -> Self {
+        Reader {
+            scanner: Scanner::new(read, Some(lenient)),
+            base: Base::new(base),
         }
     }
 }
@@ -86,55 +79,42 @@ impl<R: Read> Reader<R> {
 impl<R: Read> Iterator for Reader<R> {
     type Item = Result<u8, InError>;
     fn next(&mut self) -> Option<Self::Item> {
-        let mut value = 0u8;
-        let msi = (self.base.digits_per_byte - 1) as i8;
-        let mut i = msi as i8;
-        while i >= 0 {
-            let in_byte = self.next_non_whitespace();
-            match in_byte {
-                None => {
-                    return if i == msi {
-                        None
-                    } else {
-                        Some(Err(InError::ShortIO {
-                            bytes: (msi - i) as usize,
-                            expected: self.base.digits_per_byte as usize,
-                        }))
-                    }
-                }
-                Some(in_byte) => match in_byte {
-                    Ok(in_byte) => {
-                        let in_char = in_byte as char;
-                        if let Some(digit) = self.base.valid(in_char) {
-                            value += digit * self.base.base.pow(i as u32);
-                        } else {
-                            return Some(Err(InError::InvalidByte(in_char)));
-                        }
-                    }
-                    Err(e) => {
-                        return Some(Err(InError::StdIO(e)));
-                    }
-                },
-            }
-            i -= 1;
-        }
-        Some(Ok(value))
+        let base = &self.base;
+        self.scanner
+            .next_byte(base.digits_per_byte as usize, base.base as u32, |c| {
+                base.valid(c)
+            })
+    }
+}
+
+impl<R: Read> ByteReader for Reader<R> {
+    fn location(&self) -> Location {
+        self.scanner.location()
     }
 }
 
 /// Writes bytes to the output stream in the provided numeric base format
 ///
 /// Produced characters depend on the particular numeric base, in any case in the range ('0', '9') and ('a','z')
+/// (or ('A', 'Z'), plus separators and prefixes, as specified by the provided [Format])
+///
+/// [Format]: crate::text::Format
 pub struct Writer<W: Write> {
     out_bytes: W,
     base: Base,
+    formatter: Formatter,
 }
 
 impl<W: Write> Writer<W> {
     pub fn new(out_bytes: W, base: u8) -> Self {
+        Self::with_format(out_bytes, base, Format::default())
+    }
+
+    pub fn with_format(out_bytes: W, base: u8, format: Format) -> Self {
         Writer {
             out_bytes,
             base: Base::new(base),
+            formatter: Formatter::new(format),
         }
     }
 }
@@ -154,11 +134,11 @@ impl<W: Write> ByteWriter for Writer<W> {
                 break;
             }
         }
-        util::write(
-            &mut self.out_bytes,
-            string.as_slice(),
-            self.base.digits_per_byte as usize,
-        )
+        self.formatter.write(&mut self.out_bytes, string.as_slice())
+    }
+
+    fn finish(&mut self) -> Result<(), OutError> {
+        self.formatter.finish(&mut self.out_bytes)
     }
 }
 
@@ -193,8 +173,7 @@ mod tests {
     fn base_valid_digits() {
         for b in 2..37 {
             let base = Base::new(b);
-            for value in 0..DIGITS.len() {
-                let digit = DIGITS[value];
+            for (value, &digit) in DIGITS.iter().enumerate() {
                 let in_chars = [digit as char, (digit as char).to_ascii_uppercase()];
                 for in_char in in_chars {
                     let result = base.valid(in_char);
@@ -233,6 +212,21 @@ mod tests {
         assert!(reader.next().is_none());
     }
 
+    #[test]
+    fn b10_read() {
+        let input = [_2, _5, _5, _2, _5, _6];
+        let mut reader = Reader::new(input.as_slice(), 10);
+        assert_eq!(255u8, reader.next().unwrap().unwrap());
+        assert!(matches!(
+            reader.next(),
+            Some(Err(InError::InvalidByte('6')))
+        ));
+        let input = b"(255, 7, 010)";
+        let reader = Reader::lenient(input.as_slice(), 10, Lenient::default());
+        let output: Vec<u8> = reader.map(|b| b.unwrap()).collect();
+        assert_eq!([255u8, 7, 10].as_slice(), output);
+    }
+
     #[test]
     fn b2_write() {
         let input = 0b10110100u8;
@@ -253,6 +247,22 @@ mod tests {
         assert_eq!(expected, output);
     }
 
+    #[test]
+    fn b36_write_format() {
+        let format = Format {
+            separator: " ".to_string(),
+            uppercase: true,
+            newline: true,
+            ..Default::default()
+        };
+        let mut output = vec![];
+        let mut writer = Writer::with_format(&mut output, 36, format);
+        writer.write(35).unwrap();
+        writer.write(36).unwrap();
+        writer.finish().unwrap();
+        assert_eq!([_0, b'Z', b' ', _1, _0, b'\n'].as_slice(), output);
+    }
+
     #[test]
     fn b36_read() {
         let mut input = [_0; DIGITS.len() * 2];
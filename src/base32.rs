@@ -0,0 +1,322 @@
+use crate::byte_reader::ByteReader;
+use crate::byte_writer::ByteWriter;
+use crate::error::{InError, Location, OutError};
+use crate::util;
+use std::io::{Read, Write};
+
+const PAD: u8 = b'=';
+
+/// Base32 alphabet
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Alphabet {
+    /// [RFC 4648](https://www.rfc-editor.org/rfc/rfc4648) alphabet: 'A'-'Z' and '2'-'7'
+    Standard,
+    /// [RFC 4648](https://www.rfc-editor.org/rfc/rfc4648) extended hex alphabet: '0'-'9' and 'A'-'V'
+    Hex,
+    /// [Crockford's](https://www.crockford.com/base32.html) alphabet: '0'-'9' and 'A'-'Z' except
+    /// 'I', 'L', 'O' and 'U'; not padded
+    Crockford,
+}
+
+impl Alphabet {
+    fn symbols(&self) -> &'static [u8; 32] {
+        match self {
+            Alphabet::Standard => b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567",
+            Alphabet::Hex => b"0123456789ABCDEFGHIJKLMNOPQRSTUV",
+            Alphabet::Crockford => b"0123456789ABCDEFGHJKMNPQRSTVWXYZ",
+        }
+    }
+    fn padded(&self) -> bool {
+        *self != Alphabet::Crockford
+    }
+    fn valid(&self, c: u8) -> Option<u8> {
+        let c = c.to_ascii_uppercase();
+        match self {
+            Alphabet::Standard => match c {
+                b'A'..=b'Z' => Some(c - b'A'),
+                b'2'..=b'7' => Some(26 + (c - b'2')),
+                _ => None,
+            },
+            Alphabet::Hex => match c {
+                b'0'..=b'9' => Some(c - b'0'),
+                b'A'..=b'V' => Some(10 + (c - b'A')),
+                _ => None,
+            },
+            Alphabet::Crockford => match c {
+                b'O' => Some(0),
+                b'I' | b'L' => Some(1),
+                b'U' => None,
+                _ => self.symbols().iter().position(|&s| s == c).map(|d| d as u8),
+            },
+        }
+    }
+}
+
+/// An iterator over Result<u8,[InError]>
+///
+/// Reads bytes from the input stream in base32 format: every character of the selected
+/// [Alphabet] codes 5 bits, case is not significant. Trailing '=' padding is optional; in
+/// Crockford's alphabet 'I' and 'L' are read as '1', 'O' as '0' and hyphens are skipped. Any
+/// whitespace character is allowed and skipped.
+///
+/// [InError]: crate::error::InError
+pub struct Reader<R: Read> {
+    in_bytes: util::Input<R>,
+    alphabet: Alphabet,
+    buffer: u16,
+    bits: u8,
+    symbols: usize,
+    padded: bool,
+}
+
+impl<R: Read> Reader<R> {
+    pub fn new(read: R, alphabet: Alphabet) -> Self {
+        Reader {
+            in_bytes: util::Input::new(read),
+            alphabet,
+            buffer: 0,
+            bits: 0,
+            symbols: 0,
+            padded: false,
+        }
+    }
+
+    fn next_non_whitespace(&mut self) -> Option<<util::Input<R> as Iterator>::Item> {
+        loop {
+            let c = self.in_bytes.next()?;
+            match c {
+                Ok(c) => {
+                    if c.is_ascii_whitespace()
+                        || (c == b'-' && self.alphabet == Alphabet::Crockford)
+                    {
+                        continue;
+                    } else {
+                        return Some(Ok(c));
+                    }
+                }
+                Err(e) => {
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+
+    /// Drops the bits left over at the end of the stream, which must be less than a symbol
+    fn end(&mut self) -> Result<(), InError> {
+        let bits = self.bits;
+        self.bits = 0;
+        if bits < 5 {
+            Ok(())
+        } else {
+            Err(InError::ShortIO {
+                bytes: self.symbols % 8,
+                expected: 8,
+            })
+        }
+    }
+}
+
+impl<R: Read> Iterator for Reader<R> {
+    type Item = Result<u8, InError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.bits < 8 {
+            let c = match self.next_non_whitespace() {
+                None => {
+                    return match self.end() {
+                        Ok(()) => None,
+                        Err(e) => Some(Err(e)),
+                    }
+                }
+                Some(Ok(c)) => c,
+                Some(Err(e)) => return Some(Err(InError::StdIO(e))),
+            };
+            if c == PAD
+                && self.alphabet.padded()
+                && (self.padded || !self.symbols.is_multiple_of(8))
+            {
+                if !self.padded {
+                    self.padded = true;
+                    if let Err(e) = self.end() {
+                        return Some(Err(e));
+                    }
+                }
+                continue;
+            }
+            match (self.padded, self.alphabet.valid(c)) {
+                (false, Some(value)) => {
+                    self.buffer = (self.buffer << 5) | value as u16;
+                    self.bits += 5;
+                    self.symbols += 1;
+                }
+                _ => return Some(Err(InError::InvalidByte(c as char))),
+            }
+        }
+        self.bits -= 8;
+        Some(Ok((self.buffer >> self.bits) as u8))
+    }
+}
+
+impl<R: Read> ByteReader for Reader<R> {
+    fn location(&self) -> Location {
+        self.in_bytes.location()
+    }
+}
+
+/// Writes bytes to the output stream in base32 format
+///
+/// Produced characters belong to the selected [Alphabet] (upper case); every byte is split in 5
+/// bits groups, so the last (padded) symbols are only written on [finish].
+///
+/// [finish]: crate::byte_writer::ByteWriter::finish
+pub struct Writer<W: Write> {
+    out_bytes: W,
+    alphabet: Alphabet,
+    buffer: u16,
+    bits: u8,
+    symbols: usize,
+}
+
+impl<W: Write> Writer<W> {
+    pub fn new(out_bytes: W, alphabet: Alphabet) -> Self {
+        Writer {
+            out_bytes,
+            alphabet,
+            buffer: 0,
+            bits: 0,
+            symbols: 0,
+        }
+    }
+}
+
+impl<W: Write> ByteWriter for Writer<W> {
+    fn write(&mut self, byte: u8) -> Result<(), OutError> {
+        self.buffer = (self.buffer << 8) | byte as u16;
+        self.bits += 8;
+        let mut string = [0u8; 2];
+        let mut len = 0;
+        while self.bits >= 5 {
+            self.bits -= 5;
+            string[len] = self.alphabet.symbols()[((self.buffer >> self.bits) & 0x1f) as usize];
+            len += 1;
+        }
+        self.symbols += len;
+        util::write(&mut self.out_bytes, &string[..len], len)
+    }
+
+    fn finish(&mut self) -> Result<(), OutError> {
+        let mut string = vec![];
+        if self.bits > 0 {
+            string
+                .push(self.alphabet.symbols()[((self.buffer << (5 - self.bits)) & 0x1f) as usize]);
+            self.bits = 0;
+            self.symbols += 1;
+        }
+        if self.alphabet.padded() {
+            while !self.symbols.is_multiple_of(8) {
+                string.push(PAD);
+                self.symbols += 1;
+            }
+        }
+        util::write(&mut self.out_bytes, string.as_slice(), string.len())?;
+        util::flush(&mut self.out_bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(input: &[u8], alphabet: Alphabet) -> Vec<u8> {
+        let mut output = vec![];
+        let mut writer = Writer::new(&mut output, alphabet);
+        for b in input {
+            writer.write(*b).unwrap();
+        }
+        writer.finish().unwrap();
+        output
+    }
+
+    fn decode(input: &[u8], alphabet: Alphabet) -> Vec<u8> {
+        Reader::new(input, alphabet).map(|b| b.unwrap()).collect()
+    }
+
+    #[test]
+    fn read() {
+        assert_eq!(b"fooba".as_slice(), decode(b"MZXW6YTB", Alphabet::Standard));
+        assert_eq!(b"foob".as_slice(), decode(b"MZXW6YQ=", Alphabet::Standard));
+        assert_eq!(b"foob".as_slice(), decode(b"mzxw 6yq", Alphabet::Standard));
+        assert_eq!(b"f".as_slice(), decode(b"MY======", Alphabet::Standard));
+        assert_eq!(
+            b"foobar".as_slice(),
+            decode(b"CPNMUOJ1E8======", Alphabet::Hex)
+        );
+    }
+
+    #[test]
+    fn read_crockford() {
+        assert_eq!(
+            b"foobar".as_slice(),
+            decode(b"CSQPY-RK1E8", Alphabet::Crockford)
+        );
+        assert_eq!(
+            b"foobar".as_slice(),
+            decode(b"csqpyrkle8", Alphabet::Crockford)
+        );
+        assert_eq!(
+            decode(b"0O", Alphabet::Crockford),
+            decode(b"oo", Alphabet::Crockford)
+        );
+        let mut reader = Reader::new(b"CSQPYRKU".as_slice(), Alphabet::Crockford);
+        for _ in 0..4 {
+            reader.next().unwrap().unwrap();
+        }
+        assert!(matches!(
+            reader.next(),
+            Some(Err(InError::InvalidByte('U')))
+        ));
+    }
+
+    #[test]
+    fn read_invalid() {
+        let mut reader = Reader::new(b"MZX".as_slice(), Alphabet::Standard);
+        reader.next().unwrap().unwrap();
+        assert!(matches!(
+            reader.next(),
+            Some(Err(InError::ShortIO {
+                bytes: 3,
+                expected: 8
+            }))
+        ));
+        assert!(reader.next().is_none());
+        let mut reader = Reader::new(b"MY======MY".as_slice(), Alphabet::Standard);
+        reader.next().unwrap().unwrap();
+        assert!(matches!(
+            reader.next(),
+            Some(Err(InError::InvalidByte('M')))
+        ));
+        let mut reader = Reader::new(b"M1".as_slice(), Alphabet::Standard);
+        assert!(matches!(
+            reader.next(),
+            Some(Err(InError::InvalidByte('1')))
+        ));
+    }
+
+    #[test]
+    fn write() {
+        assert_eq!(b"".as_slice(), encode(b"", Alphabet::Standard));
+        assert_eq!(b"MY======".as_slice(), encode(b"f", Alphabet::Standard));
+        assert_eq!(b"MZXW6YQ=".as_slice(), encode(b"foob", Alphabet::Standard));
+        assert_eq!(
+            b"MZXW6YTBOI======".as_slice(),
+            encode(b"foobar", Alphabet::Standard)
+        );
+        assert_eq!(
+            b"CPNMUOJ1E8======".as_slice(),
+            encode(b"foobar", Alphabet::Hex)
+        );
+        assert_eq!(
+            b"CSQPYRK1E8".as_slice(),
+            encode(b"foobar", Alphabet::Crockford)
+        );
+    }
+}
@@ -0,0 +1,294 @@
+use crate::byte_reader::ByteReader;
+use crate::byte_writer::ByteWriter;
+use crate::error::{InError, Location, OutError};
+use crate::util;
+use std::io::{Read, Write};
+
+const PAD: u8 = b'=';
+
+/// Base64 alphabet, as defined in [RFC 4648](https://www.rfc-editor.org/rfc/rfc4648)
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Alphabet {
+    /// standard alphabet: 'A'-'Z', 'a'-'z', '0'-'9', '+' and '/'
+    Standard,
+    /// URL and filename safe alphabet: 'A'-'Z', 'a'-'z', '0'-'9', '-' and '_'
+    UrlSafe,
+}
+
+impl Alphabet {
+    fn symbols(&self) -> &'static [u8; 64] {
+        match self {
+            Alphabet::Standard => {
+                b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/"
+            }
+            Alphabet::UrlSafe => {
+                b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_"
+            }
+        }
+    }
+    fn valid(&self, c: u8) -> Option<u8> {
+        match c {
+            b'A'..=b'Z' => Some(c - b'A'),
+            b'a'..=b'z' => Some(26 + (c - b'a')),
+            b'0'..=b'9' => Some(52 + (c - b'0')),
+            b'+' if *self == Alphabet::Standard => Some(62),
+            b'/' if *self == Alphabet::Standard => Some(63),
+            b'-' if *self == Alphabet::UrlSafe => Some(62),
+            b'_' if *self == Alphabet::UrlSafe => Some(63),
+            _ => None,
+        }
+    }
+}
+
+/// An iterator over Result<u8,[InError]>
+///
+/// Reads bytes from the input stream in base64 format: every 4 characters of the selected
+/// [Alphabet] code 3 bytes; the last quantum may be padded with '=' characters (padding can also
+/// be omitted). Any whitespace character is allowed and skipped.
+///
+/// [InError]: crate::error::InError
+pub struct Reader<R: Read> {
+    in_bytes: util::Input<R>,
+    alphabet: Alphabet,
+    decoded: [u8; 3],
+    available: usize,
+    consumed: usize,
+    padded: bool,
+}
+
+impl<R: Read> Reader<R> {
+    pub fn new(read: R, alphabet: Alphabet) -> Self {
+        Reader {
+            in_bytes: util::Input::new(read),
+            alphabet,
+            decoded: [0u8; 3],
+            available: 0,
+            consumed: 0,
+            padded: false,
+        }
+    }
+
+    fn next_non_whitespace(&mut self) -> Option<<util::Input<R> as Iterator>::Item> {
+        loop {
+            let c = self.in_bytes.next()?;
+            match c {
+                Ok(c) => {
+                    if c.is_ascii_whitespace() {
+                        continue;
+                    } else {
+                        return Some(Ok(c));
+                    }
+                }
+                Err(e) => {
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+
+    /// Decodes next quantum (up to 4 characters) into self.decoded
+    fn decode_quantum(&mut self) -> Option<Result<(), InError>> {
+        let mut sextets = [0u8; 4];
+        let mut symbols = 0usize;
+        let mut pads = 0usize;
+        while symbols + pads < 4 {
+            let c = match self.next_non_whitespace() {
+                None => break,
+                Some(Ok(c)) => c,
+                Some(Err(e)) => return Some(Err(InError::StdIO(e))),
+            };
+            if self.padded {
+                return Some(Err(InError::InvalidByte(c as char)));
+            }
+            if c == PAD && symbols >= 2 {
+                pads += 1;
+            } else if let (0, Some(sextet)) = (pads, self.alphabet.valid(c)) {
+                sextets[symbols] = sextet;
+                symbols += 1;
+            } else {
+                return Some(Err(InError::InvalidByte(c as char)));
+            }
+        }
+        match symbols {
+            0 => None,
+            1 => Some(Err(InError::ShortIO {
+                bytes: 1,
+                expected: 4,
+            })),
+            _ => {
+                self.padded = pads > 0;
+                self.decoded = [
+                    (sextets[0] << 2) | (sextets[1] >> 4),
+                    (sextets[1] << 4) | (sextets[2] >> 2),
+                    (sextets[2] << 6) | sextets[3],
+                ];
+                self.available = symbols - 1;
+                self.consumed = 0;
+                Some(Ok(()))
+            }
+        }
+    }
+}
+
+impl<R: Read> Iterator for Reader<R> {
+    type Item = Result<u8, InError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.consumed == self.available {
+            if let Err(e) = self.decode_quantum()? {
+                return Some(Err(e));
+            }
+        }
+        self.consumed += 1;
+        Some(Ok(self.decoded[self.consumed - 1]))
+    }
+}
+
+impl<R: Read> ByteReader for Reader<R> {
+    fn location(&self) -> Location {
+        self.in_bytes.location()
+    }
+}
+
+/// Writes bytes to the output stream in base64 format
+///
+/// Produced characters belong to the selected [Alphabet]; every 3 bytes are written as 4
+/// characters, so the last (padded) quantum is only written on [finish].
+///
+/// [finish]: crate::byte_writer::ByteWriter::finish
+pub struct Writer<W: Write> {
+    out_bytes: W,
+    alphabet: Alphabet,
+    quantum: [u8; 3],
+    len: usize,
+}
+
+impl<W: Write> Writer<W> {
+    pub fn new(out_bytes: W, alphabet: Alphabet) -> Self {
+        Writer {
+            out_bytes,
+            alphabet,
+            quantum: [0u8; 3],
+            len: 0,
+        }
+    }
+
+    fn encode_quantum(&mut self) -> Result<(), OutError> {
+        let symbols = self.alphabet.symbols();
+        let q = self.quantum;
+        let mut string = [
+            symbols[(q[0] >> 2) as usize],
+            symbols[(((q[0] & 0x03) << 4) | (q[1] >> 4)) as usize],
+            symbols[(((q[1] & 0x0f) << 2) | (q[2] >> 6)) as usize],
+            symbols[(q[2] & 0x3f) as usize],
+        ];
+        for c in string.iter_mut().skip(self.len + 1) {
+            *c = PAD;
+        }
+        self.quantum = [0u8; 3];
+        self.len = 0;
+        util::write(&mut self.out_bytes, string.as_slice(), 4)
+    }
+}
+
+impl<W: Write> ByteWriter for Writer<W> {
+    fn write(&mut self, byte: u8) -> Result<(), OutError> {
+        self.quantum[self.len] = byte;
+        self.len += 1;
+        if self.len == 3 {
+            self.encode_quantum()
+        } else {
+            Ok(())
+        }
+    }
+
+    fn finish(&mut self) -> Result<(), OutError> {
+        if self.len > 0 {
+            self.encode_quantum()?;
+        }
+        util::flush(&mut self.out_bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(input: &[u8], alphabet: Alphabet) -> Vec<u8> {
+        let mut output = vec![];
+        let mut writer = Writer::new(&mut output, alphabet);
+        for b in input {
+            writer.write(*b).unwrap();
+        }
+        writer.finish().unwrap();
+        output
+    }
+
+    #[test]
+    fn read() {
+        let input = b"TWFu\nTWFu\r\nTQ==";
+        let reader = Reader::new(input.as_slice(), Alphabet::Standard);
+        let output: Vec<u8> = reader.map(|b| b.unwrap()).collect();
+        assert_eq!(b"ManManM".as_slice(), output);
+        let reader = Reader::new(b"TWE=".as_slice(), Alphabet::Standard);
+        let output: Vec<u8> = reader.map(|b| b.unwrap()).collect();
+        assert_eq!(b"Ma".as_slice(), output);
+    }
+
+    #[test]
+    fn read_unpadded() {
+        let reader = Reader::new(b"TWFuTQ".as_slice(), Alphabet::Standard);
+        let output: Vec<u8> = reader.map(|b| b.unwrap()).collect();
+        assert_eq!(b"ManM".as_slice(), output);
+    }
+
+    #[test]
+    fn read_url_safe() {
+        let mut reader = Reader::new(b"-_8=".as_slice(), Alphabet::UrlSafe);
+        assert_eq!(0xfb, reader.next().unwrap().unwrap());
+        assert_eq!(0xff, reader.next().unwrap().unwrap());
+        assert!(reader.next().is_none());
+        let mut reader = Reader::new(b"+/8=".as_slice(), Alphabet::UrlSafe);
+        assert!(matches!(
+            reader.next(),
+            Some(Err(InError::InvalidByte('+')))
+        ));
+    }
+
+    #[test]
+    fn read_invalid() {
+        let mut reader = Reader::new(b"TQ==TQ==".as_slice(), Alphabet::Standard);
+        assert_eq!(b'M', reader.next().unwrap().unwrap());
+        assert!(matches!(
+            reader.next(),
+            Some(Err(InError::InvalidByte('T')))
+        ));
+        let mut reader = Reader::new(b"TWFuT".as_slice(), Alphabet::Standard);
+        for _ in 0..3 {
+            reader.next().unwrap().unwrap();
+        }
+        assert!(matches!(
+            reader.next(),
+            Some(Err(InError::ShortIO {
+                bytes: 1,
+                expected: 4
+            }))
+        ));
+    }
+
+    #[test]
+    fn write() {
+        assert_eq!(b"TWFu".as_slice(), encode(b"Man", Alphabet::Standard));
+        assert_eq!(b"TWE=".as_slice(), encode(b"Ma", Alphabet::Standard));
+        assert_eq!(b"TQ==".as_slice(), encode(b"M", Alphabet::Standard));
+        assert_eq!(b"".as_slice(), encode(b"", Alphabet::Standard));
+    }
+
+    #[test]
+    fn write_url_safe() {
+        assert_eq!(b"-_8=".as_slice(), encode(&[0xfb, 0xff], Alphabet::UrlSafe));
+        assert_eq!(
+            b"+/8=".as_slice(),
+            encode(&[0xfb, 0xff], Alphabet::Standard)
+        );
+    }
+}
@@ -1,23 +1,41 @@
+use crate::byte_reader::ByteReader;
 use crate::byte_writer::ByteWriter;
-use crate::error::{InError, OutError};
-use crate::util;
+use crate::error::{InError, Location, OutError};
+use crate::text::{Format, Formatter, Lenient, Scanner};
 use crate::util::literals::*;
-use std::io::{Bytes, Read, Write};
+use std::io::{Read, Write};
 
 /// An iterator over Result<u8,[InError]>
 ///
 /// Reads bytes from the input stream in binary base format, that is a multiple of 8 characters 0 and 1 are
 /// allowed (and any number of whitespace characters that will be skipped)
 ///
+/// In [Lenient] mode, separators and prefixes are allowed too.
+///
 /// [InError]: crate::error::InError
+/// [Lenient]: crate::text::Lenient
 pub struct Reader<R: Read> {
-    in_bytes: Bytes<R>,
+    scanner: Scanner<R>,
 }
 
 impl<R: Read> Reader<R> {
     pub fn new(read: R) -> Self {
         Reader {
-            in_bytes: read.bytes(),
+            scanner: Scanner::new(read, None),
+        }
+    }
+
+    pub fn lenient(read: R, lenient: Lenient) -> Self {
+        Reader {
+            scanner: Scanner::new(read, Some(lenient)),
+        }
+    }
+
+    fn valid(n: char) -> Option<u8> {
+        match n {
+            '0' => Some(0),
+            '1' => Some(1),
+            _ => None,
         }
     }
 }
@@ -25,59 +43,37 @@ impl<R: Read> Reader<R> {
 impl<R: Read> Iterator for Reader<R> {
     type Item = Result<u8, InError>;
     fn next(&mut self) -> Option<Self::Item> {
-        let mut value = 0u8;
-        let mut i = 7i8;
-        while i >= 0 {
-            let in_byte = self.in_bytes.next();
-            match in_byte {
-                None => {
-                    return if i == 7 {
-                        None
-                    } else {
-                        Some(Err(InError::ShortIO {
-                            bytes: 7 - i as usize,
-                            expected: 8,
-                        }))
-                    }
-                }
-                Some(in_byte) => match in_byte {
-                    Ok(in_byte) => {
-                        let in_byte = in_byte as char;
-                        match in_byte {
-                            '0' => {}
-                            '1' => {
-                                value |= 1 << i;
-                            }
-                            _ => {
-                                if in_byte.is_ascii_whitespace() {
-                                    continue;
-                                } else {
-                                    return Some(Err(InError::InvalidByte(in_byte)));
-                                }
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        return Some(Err(InError::StdIO(e)));
-                    }
-                },
-            }
-            i -= 1;
-        }
-        Some(Ok(value))
+        self.scanner.next_byte(8, 2, Self::valid)
+    }
+}
+
+impl<R: Read> ByteReader for Reader<R> {
+    fn location(&self) -> Location {
+        self.scanner.location()
     }
 }
 
 /// Writes bytes to the output stream in the binary format
 ///
-/// Produced characters are '0' and '1'.
+/// Produced characters are '0' and '1', plus separators and prefixes specified by the provided
+/// [Format].
+///
+/// [Format]: crate::text::Format
 pub struct Writer<W: Write> {
     out_bytes: W,
+    formatter: Formatter,
 }
 
 impl<W: Write> Writer<W> {
     pub fn new(out_bytes: W) -> Self {
-        Writer { out_bytes }
+        Self::with_format(out_bytes, Format::default())
+    }
+
+    pub fn with_format(out_bytes: W, format: Format) -> Self {
+        Writer {
+            out_bytes,
+            formatter: Formatter::new(format),
+        }
     }
 }
 
@@ -89,7 +85,12 @@ impl<W: Write> ByteWriter for Writer<W> {
                 bit_string[7 - i] = _1;
             }
         }
-        util::write(&mut self.out_bytes, bit_string.as_slice(), 8)
+        self.formatter
+            .write(&mut self.out_bytes, bit_string.as_slice())
+    }
+
+    fn finish(&mut self) -> Result<(), OutError> {
+        self.formatter.finish(&mut self.out_bytes)
     }
 }
 
@@ -108,6 +109,15 @@ mod tests {
         assert!(reader.next().is_none());
     }
 
+    #[test]
+    fn read_lenient() {
+        let input = b"0b01001010, 0b1";
+        let mut reader = Reader::lenient(input.as_slice(), Lenient::binary());
+        assert_eq!(0b01001010u8, reader.next().unwrap().unwrap());
+        assert_eq!(0b1u8, reader.next().unwrap().unwrap());
+        assert!(reader.next().is_none());
+    }
+
     #[test]
     fn write() {
         let input = 0b10110100u8;
@@ -117,6 +127,22 @@ mod tests {
         writer.write(input).unwrap();
         assert_eq!(expected, output);
     }
+
+    #[test]
+    fn write_format() {
+        let format = Format {
+            separator: ",".to_string(),
+            prefix: "0b".to_string(),
+            wrap: 1,
+            ..Default::default()
+        };
+        let mut output = vec![];
+        let mut writer = Writer::with_format(&mut output, format);
+        writer.write(0b10110100u8).unwrap();
+        writer.write(0b00000001u8).unwrap();
+        writer.finish().unwrap();
+        assert_eq!(b"0b10110100\n0b00000001".as_slice(), output);
+    }
 }
 
 #[cfg(all(test, feature = "benchmark"))]
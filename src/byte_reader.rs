@@ -0,0 +1,12 @@
+use crate::error::*;
+
+/// A trait for objects which are byte-oriented sources.
+///
+/// Byte readers are iterators over Result<u8,[InError]> which also keep track of their location
+/// in the input stream, so that errors can be located.
+///
+/// [InError]: crate::error::InError
+pub trait ByteReader: Iterator<Item = Result<u8, InError>> {
+    /// Location of the last character read from the input stream
+    fn location(&self) -> Location;
+}
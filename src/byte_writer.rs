@@ -8,4 +8,12 @@ use crate::error::*;
 /// [Error]: crate::error::OutError
 pub trait ByteWriter {
     fn write(&mut self, byte: u8) -> Result<(), OutError>;
+
+    /// Signals the end of the byte stream
+    ///
+    /// Writers coding groups of bytes (g.e. base64) write here any pending partial group, then
+    /// flush the output stream; default implementation does nothing.
+    fn finish(&mut self) -> Result<(), OutError> {
+        Ok(())
+    }
 }
@@ -0,0 +1,476 @@
+use crate::byte_reader::ByteReader;
+use crate::byte_writer::ByteWriter;
+use crate::error::{InError, Location, OutError};
+use crate::utf8::{Decoder, Invalid, Unit};
+use crate::util;
+use std::collections::VecDeque;
+use std::io::{Read, Write};
+
+/// Characters coded by Windows-1252 bytes 0x80 to 0x9f (the others match ISO-8859-1)
+const WINDOWS_1252: [Option<char>; 32] = [
+    Some('\u{20ac}'),
+    None,
+    Some('\u{201a}'),
+    Some('\u{0192}'),
+    Some('\u{201e}'),
+    Some('\u{2026}'),
+    Some('\u{2020}'),
+    Some('\u{2021}'),
+    Some('\u{02c6}'),
+    Some('\u{2030}'),
+    Some('\u{0160}'),
+    Some('\u{2039}'),
+    Some('\u{0152}'),
+    None,
+    Some('\u{017d}'),
+    None,
+    None,
+    Some('\u{2018}'),
+    Some('\u{2019}'),
+    Some('\u{201c}'),
+    Some('\u{201d}'),
+    Some('\u{2022}'),
+    Some('\u{2013}'),
+    Some('\u{2014}'),
+    Some('\u{02dc}'),
+    Some('\u{2122}'),
+    Some('\u{0161}'),
+    Some('\u{203a}'),
+    Some('\u{0153}'),
+    None,
+    Some('\u{017e}'),
+    Some('\u{0178}'),
+];
+
+/// Latin-1 code points of EBCDIC CP037 bytes
+const CP037: [u8; 256] = [
+    0x00, 0x01, 0x02, 0x03, 0x9c, 0x09, 0x86, 0x7f, 0x97, 0x8d, 0x8e, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f,
+    0x10, 0x11, 0x12, 0x13, 0x9d, 0x85, 0x08, 0x87, 0x18, 0x19, 0x92, 0x8f, 0x1c, 0x1d, 0x1e, 0x1f,
+    0x80, 0x81, 0x82, 0x83, 0x84, 0x0a, 0x17, 0x1b, 0x88, 0x89, 0x8a, 0x8b, 0x8c, 0x05, 0x06, 0x07,
+    0x90, 0x91, 0x16, 0x93, 0x94, 0x95, 0x96, 0x04, 0x98, 0x99, 0x9a, 0x9b, 0x14, 0x15, 0x9e, 0x1a,
+    0x20, 0xa0, 0xe2, 0xe4, 0xe0, 0xe1, 0xe3, 0xe5, 0xe7, 0xf1, 0xa2, 0x2e, 0x3c, 0x28, 0x2b, 0x7c,
+    0x26, 0xe9, 0xea, 0xeb, 0xe8, 0xed, 0xee, 0xef, 0xec, 0xdf, 0x21, 0x24, 0x2a, 0x29, 0x3b, 0xac,
+    0x2d, 0x2f, 0xc2, 0xc4, 0xc0, 0xc1, 0xc3, 0xc5, 0xc7, 0xd1, 0xa6, 0x2c, 0x25, 0x5f, 0x3e, 0x3f,
+    0xf8, 0xc9, 0xca, 0xcb, 0xc8, 0xcd, 0xce, 0xcf, 0xcc, 0x60, 0x3a, 0x23, 0x40, 0x27, 0x3d, 0x22,
+    0xd8, 0x61, 0x62, 0x63, 0x64, 0x65, 0x66, 0x67, 0x68, 0x69, 0xab, 0xbb, 0xf0, 0xfd, 0xfe, 0xb1,
+    0xb0, 0x6a, 0x6b, 0x6c, 0x6d, 0x6e, 0x6f, 0x70, 0x71, 0x72, 0xaa, 0xba, 0xe6, 0xb8, 0xc6, 0xa4,
+    0xb5, 0x7e, 0x73, 0x74, 0x75, 0x76, 0x77, 0x78, 0x79, 0x7a, 0xa1, 0xbf, 0xd0, 0xdd, 0xde, 0xae,
+    0x5e, 0xa3, 0xa5, 0xb7, 0xa9, 0xa7, 0xb6, 0xbc, 0xbd, 0xbe, 0x5b, 0x5d, 0xaf, 0xa8, 0xb4, 0xd7,
+    0x7b, 0x41, 0x42, 0x43, 0x44, 0x45, 0x46, 0x47, 0x48, 0x49, 0xad, 0xf4, 0xf6, 0xf2, 0xf3, 0xf5,
+    0x7d, 0x4a, 0x4b, 0x4c, 0x4d, 0x4e, 0x4f, 0x50, 0x51, 0x52, 0xb9, 0xfb, 0xfc, 0xf9, 0xfa, 0xff,
+    0x5c, 0xf7, 0x53, 0x54, 0x55, 0x56, 0x57, 0x58, 0x59, 0x5a, 0xb2, 0xd4, 0xd6, 0xd2, 0xd3, 0xd5,
+    0x30, 0x31, 0x32, 0x33, 0x34, 0x35, 0x36, 0x37, 0x38, 0x39, 0xb3, 0xdb, 0xdc, 0xd9, 0xda, 0x9f,
+];
+
+/// Latin-1 code points of EBCDIC CP500 bytes
+const CP500: [u8; 256] = [
+    0x00, 0x01, 0x02, 0x03, 0x9c, 0x09, 0x86, 0x7f, 0x97, 0x8d, 0x8e, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f,
+    0x10, 0x11, 0x12, 0x13, 0x9d, 0x85, 0x08, 0x87, 0x18, 0x19, 0x92, 0x8f, 0x1c, 0x1d, 0x1e, 0x1f,
+    0x80, 0x81, 0x82, 0x83, 0x84, 0x0a, 0x17, 0x1b, 0x88, 0x89, 0x8a, 0x8b, 0x8c, 0x05, 0x06, 0x07,
+    0x90, 0x91, 0x16, 0x93, 0x94, 0x95, 0x96, 0x04, 0x98, 0x99, 0x9a, 0x9b, 0x14, 0x15, 0x9e, 0x1a,
+    0x20, 0xa0, 0xe2, 0xe4, 0xe0, 0xe1, 0xe3, 0xe5, 0xe7, 0xf1, 0x5b, 0x2e, 0x3c, 0x28, 0x2b, 0x21,
+    0x26, 0xe9, 0xea, 0xeb, 0xe8, 0xed, 0xee, 0xef, 0xec, 0xdf, 0x5d, 0x24, 0x2a, 0x29, 0x3b, 0x5e,
+    0x2d, 0x2f, 0xc2, 0xc4, 0xc0, 0xc1, 0xc3, 0xc5, 0xc7, 0xd1, 0xa6, 0x2c, 0x25, 0x5f, 0x3e, 0x3f,
+    0xf8, 0xc9, 0xca, 0xcb, 0xc8, 0xcd, 0xce, 0xcf, 0xcc, 0x60, 0x3a, 0x23, 0x40, 0x27, 0x3d, 0x22,
+    0xd8, 0x61, 0x62, 0x63, 0x64, 0x65, 0x66, 0x67, 0x68, 0x69, 0xab, 0xbb, 0xf0, 0xfd, 0xfe, 0xb1,
+    0xb0, 0x6a, 0x6b, 0x6c, 0x6d, 0x6e, 0x6f, 0x70, 0x71, 0x72, 0xaa, 0xba, 0xe6, 0xb8, 0xc6, 0xa4,
+    0xb5, 0x7e, 0x73, 0x74, 0x75, 0x76, 0x77, 0x78, 0x79, 0x7a, 0xa1, 0xbf, 0xd0, 0xdd, 0xde, 0xae,
+    0xa2, 0xa3, 0xa5, 0xb7, 0xa9, 0xa7, 0xb6, 0xbc, 0xbd, 0xbe, 0xac, 0x7c, 0xaf, 0xa8, 0xb4, 0xd7,
+    0x7b, 0x41, 0x42, 0x43, 0x44, 0x45, 0x46, 0x47, 0x48, 0x49, 0xad, 0xf4, 0xf6, 0xf2, 0xf3, 0xf5,
+    0x7d, 0x4a, 0x4b, 0x4c, 0x4d, 0x4e, 0x4f, 0x50, 0x51, 0x52, 0xb9, 0xfb, 0xfc, 0xf9, 0xfa, 0xff,
+    0x5c, 0xf7, 0x53, 0x54, 0x55, 0x56, 0x57, 0x58, 0x59, 0x5a, 0xb2, 0xd4, 0xd6, 0xd2, 0xd3, 0xd5,
+    0x30, 0x31, 0x32, 0x33, 0x34, 0x35, 0x36, 0x37, 0x38, 0x39, 0xb3, 0xdb, 0xdc, 0xd9, 0xda, 0x9f,
+];
+
+/// Latin-1 code points of EBCDIC CP1047 bytes
+const CP1047: [u8; 256] = [
+    0x00, 0x01, 0x02, 0x03, 0x9c, 0x09, 0x86, 0x7f, 0x97, 0x8d, 0x8e, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f,
+    0x10, 0x11, 0x12, 0x13, 0x9d, 0x85, 0x08, 0x87, 0x18, 0x19, 0x92, 0x8f, 0x1c, 0x1d, 0x1e, 0x1f,
+    0x80, 0x81, 0x82, 0x83, 0x84, 0x0a, 0x17, 0x1b, 0x88, 0x89, 0x8a, 0x8b, 0x8c, 0x05, 0x06, 0x07,
+    0x90, 0x91, 0x16, 0x93, 0x94, 0x95, 0x96, 0x04, 0x98, 0x99, 0x9a, 0x9b, 0x14, 0x15, 0x9e, 0x1a,
+    0x20, 0xa0, 0xe2, 0xe4, 0xe0, 0xe1, 0xe3, 0xe5, 0xe7, 0xf1, 0xa2, 0x2e, 0x3c, 0x28, 0x2b, 0x7c,
+    0x26, 0xe9, 0xea, 0xeb, 0xe8, 0xed, 0xee, 0xef, 0xec, 0xdf, 0x21, 0x24, 0x2a, 0x29, 0x3b, 0x5e,
+    0x2d, 0x2f, 0xc2, 0xc4, 0xc0, 0xc1, 0xc3, 0xc5, 0xc7, 0xd1, 0xa6, 0x2c, 0x25, 0x5f, 0x3e, 0x3f,
+    0xf8, 0xc9, 0xca, 0xcb, 0xc8, 0xcd, 0xce, 0xcf, 0xcc, 0x60, 0x3a, 0x23, 0x40, 0x27, 0x3d, 0x22,
+    0xd8, 0x61, 0x62, 0x63, 0x64, 0x65, 0x66, 0x67, 0x68, 0x69, 0xab, 0xbb, 0xf0, 0xfd, 0xfe, 0xb1,
+    0xb0, 0x6a, 0x6b, 0x6c, 0x6d, 0x6e, 0x6f, 0x70, 0x71, 0x72, 0xaa, 0xba, 0xe6, 0xb8, 0xc6, 0xa4,
+    0xb5, 0x7e, 0x73, 0x74, 0x75, 0x76, 0x77, 0x78, 0x79, 0x7a, 0xa1, 0xbf, 0xd0, 0x5b, 0xde, 0xae,
+    0xac, 0xa3, 0xa5, 0xb7, 0xa9, 0xa7, 0xb6, 0xbc, 0xbd, 0xbe, 0xdd, 0xa8, 0xaf, 0x5d, 0xb4, 0xd7,
+    0x7b, 0x41, 0x42, 0x43, 0x44, 0x45, 0x46, 0x47, 0x48, 0x49, 0xad, 0xf4, 0xf6, 0xf2, 0xf3, 0xf5,
+    0x7d, 0x4a, 0x4b, 0x4c, 0x4d, 0x4e, 0x4f, 0x50, 0x51, 0x52, 0xb9, 0xfb, 0xfc, 0xf9, 0xfa, 0xff,
+    0x5c, 0xf7, 0x53, 0x54, 0x55, 0x56, 0x57, 0x58, 0x59, 0x5a, 0xb2, 0xd4, 0xd6, 0xd2, 0xd3, 0xd5,
+    0x30, 0x31, 0x32, 0x33, 0x34, 0x35, 0x36, 0x37, 0x38, 0x39, 0xb3, 0xdb, 0xdc, 0xd9, 0xda, 0x9f,
+];
+
+/// Single byte character encoding
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum CodePage {
+    /// ISO-8859-1 (Latin-1): every byte codes the code point with the same value
+    Latin1,
+    /// Windows-1252: ISO-8859-1 with printable characters (g.e. '€') instead of the C1 control
+    /// codes 0x80 to 0x9f; bytes 0x81, 0x8d, 0x8f, 0x90 and 0x9d are undefined
+    Windows1252,
+    /// EBCDIC CP037 (US/Canada), coding the ISO-8859-1 characters (g.e. 0xc1 for 'A')
+    Cp037,
+    /// EBCDIC CP500 (International): CP037 with some punctuation moved (g.e. 0x4a for '[')
+    Cp500,
+    /// EBCDIC CP1047 (Latin-1 Open Systems, z/OS Unix): CP037 with brackets, '^' and '¬' moved
+    /// (g.e. 0xad for '[')
+    Cp1047,
+}
+
+impl CodePage {
+    /// Character coded by the provided byte, if defined
+    fn decode(self, byte: u8) -> Option<char> {
+        match (self, self.ebcdic(), byte) {
+            (_, Some(table), _) => Some(table[byte as usize] as char),
+            (CodePage::Windows1252, _, 0x80..=0x9f) => WINDOWS_1252[byte as usize - 0x80],
+            _ => Some(byte as char),
+        }
+    }
+
+    /// Byte coding the provided character, if any
+    fn encode(self, c: char) -> Option<u8> {
+        if let Some(table) = self.ebcdic() {
+            let latin1 = u8::try_from(c as u32).ok()?;
+            return table.iter().position(|&b| b == latin1).map(|i| i as u8);
+        }
+        match (self, c as u32) {
+            (CodePage::Windows1252, 0x80..=0x9f) => None,
+            (_, 0..=0xff) => Some(c as u8),
+            (CodePage::Windows1252, _) => WINDOWS_1252
+                .iter()
+                .position(|&d| d == Some(c))
+                .map(|i| 0x80 + i as u8),
+            _ => None,
+        }
+    }
+
+    /// Table of EBCDIC code pages
+    fn ebcdic(self) -> Option<&'static [u8; 256]> {
+        match self {
+            CodePage::Cp037 => Some(&CP037),
+            CodePage::Cp500 => Some(&CP500),
+            CodePage::Cp1047 => Some(&CP1047),
+            CodePage::Latin1 | CodePage::Windows1252 => None,
+        }
+    }
+}
+
+/// An iterator over Result<u8,[InError]>
+///
+/// Reads bytes from the input stream as text in the provided [CodePage], returning the bytes of
+/// the same text encoded as UTF-8; undefined bytes are [InvalidByte] errors.
+///
+/// If a record length is provided, the input is split in fixed length records (as stored by
+/// mainframes), a newline being returned after every record.
+///
+/// [InError]: crate::error::InError
+/// [InvalidByte]: crate::error::ErrorType::InvalidByte
+pub struct Reader<R: Read> {
+    in_bytes: util::Input<R>,
+    code_page: CodePage,
+    record_length: usize,
+    column: usize,
+    bytes: VecDeque<u8>,
+}
+
+impl<R: Read> Reader<R> {
+    pub fn new(read: R, code_page: CodePage) -> Self {
+        Self::with_records(read, code_page, 0)
+    }
+
+    /// Builds a reader of records with the provided length (no records if 0)
+    pub fn with_records(read: R, code_page: CodePage, record_length: usize) -> Self {
+        Reader {
+            in_bytes: util::Input::new(read),
+            code_page,
+            record_length,
+            column: 0,
+            bytes: VecDeque::new(),
+        }
+    }
+}
+
+impl<R: Read> Iterator for Reader<R> {
+    type Item = Result<u8, InError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.bytes.is_empty() {
+            let byte = match self.in_bytes.next()? {
+                Ok(byte) => byte,
+                Err(e) => return Some(Err(InError::StdIO(e))),
+            };
+            let c = self.code_page.decode(byte);
+            if let Some(c) = c {
+                self.bytes.extend(c.encode_utf8(&mut [0u8; 4]).as_bytes());
+            }
+            self.column += 1;
+            if self.column == self.record_length {
+                self.column = 0;
+                self.bytes.push_back(b'\n');
+            }
+            if c.is_none() {
+                return Some(Err(InError::InvalidByte(byte as char)));
+            }
+        }
+        self.bytes.pop_front().map(Ok)
+    }
+}
+
+impl<R: Read> ByteReader for Reader<R> {
+    fn location(&self) -> Location {
+        self.in_bytes.location()
+    }
+}
+
+/// Writes bytes to the output stream, as UTF-8 text encoded in the provided [CodePage]
+///
+/// Invalid UTF-8 sequences, as well as characters not in the code page, are written according to
+/// the provided [Invalid] representation: with the default one they are an error (an
+/// [InvalidByte] with the lead byte of the character, if not in the code page); with
+/// [Replace], they are written as '?'; with [Escape], as the '\xHH' escape sequences of their
+/// UTF-8 bytes.
+///
+/// If a record length is provided, every line is written as a fixed length record padded with
+/// spaces (a line longer than that continuing in the following records), without newlines.
+///
+/// [InvalidByte]: crate::error::ErrorType::InvalidByte
+/// [Replace]: Invalid::Replace
+/// [Escape]: Invalid::Escape
+pub struct Writer<W: Write> {
+    out_bytes: W,
+    code_page: CodePage,
+    invalid: Invalid,
+    record_length: usize,
+    column: usize,
+    decoder: Decoder,
+}
+
+impl<W: Write> Writer<W> {
+    pub fn new(out_bytes: W, code_page: CodePage) -> Self {
+        Self::with_options(out_bytes, code_page, Invalid::default(), 0)
+    }
+
+    /// Builds a writer of records with the provided length (no records if 0)
+    pub fn with_options(
+        out_bytes: W,
+        code_page: CodePage,
+        invalid: Invalid,
+        record_length: usize,
+    ) -> Self {
+        Writer {
+            out_bytes,
+            code_page,
+            invalid,
+            record_length,
+            column: 0,
+            decoder: Decoder::new(),
+        }
+    }
+
+    fn write_byte(&mut self, byte: u8) -> Result<(), OutError> {
+        if self.column == self.record_length {
+            self.column = 0;
+        }
+        self.column += 1;
+        util::write(&mut self.out_bytes, &[byte], 1)
+    }
+
+    /// Writes the provided ASCII text
+    fn write_ascii(&mut self, text: &str) -> Result<(), OutError> {
+        for c in text.chars() {
+            self.write_byte(self.code_page.encode(c).unwrap())?;
+        }
+        Ok(())
+    }
+
+    /// Pads the current record with spaces
+    fn end_record(&mut self) -> Result<(), OutError> {
+        let space = self.code_page.encode(' ').unwrap();
+        for _ in self.column..self.record_length {
+            self.write_byte(space)?;
+        }
+        self.column = 0;
+        Ok(())
+    }
+
+    /// Writes the provided invalid bytes, according to self.invalid
+    fn write_invalid(&mut self, bytes: &[u8], error: OutError) -> Result<(), OutError> {
+        match self.invalid {
+            Invalid::Strict => Err(error),
+            Invalid::Replace => self.write_ascii("?"),
+            Invalid::Escape => {
+                let escaped: String = bytes.iter().map(|b| format!("\\x{b:02x}")).collect();
+                self.write_ascii(&escaped)
+            }
+        }
+    }
+
+    /// Writes the units decoded so far
+    fn write_units(&mut self) -> Result<(), OutError> {
+        while let Some(unit) = self.decoder.next() {
+            match unit {
+                Unit::Char('\n') if self.record_length > 0 => self.end_record()?,
+                Unit::Char(c) => match self.code_page.encode(c) {
+                    Some(b) => self.write_byte(b)?,
+                    None => {
+                        let mut utf8 = [0u8; 4];
+                        let bytes = c.encode_utf8(&mut utf8).as_bytes();
+                        self.write_invalid(bytes, OutError::InvalidByte(bytes[0]))?;
+                    }
+                },
+                Unit::Invalid(malformed, bytes) => {
+                    self.write_invalid(&bytes, malformed.error(|b| b))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write> ByteWriter for Writer<W> {
+    fn write(&mut self, byte: u8) -> Result<(), OutError> {
+        self.decoder.push(byte);
+        self.write_units()
+    }
+
+    fn finish(&mut self) -> Result<(), OutError> {
+        self.decoder.end();
+        self.write_units()?;
+        if self.column > 0 {
+            self.end_record()?;
+        }
+        util::flush(&mut self.out_bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(input: &[u8], code_page: CodePage) -> Vec<Result<u8, InError>> {
+        Reader::new(input, code_page).collect()
+    }
+
+    fn encode(input: &[u8], code_page: CodePage, invalid: Invalid) -> Result<Vec<u8>, OutError> {
+        let mut output = vec![];
+        let mut writer = Writer::with_options(&mut output, code_page, invalid, 0);
+        for b in input {
+            writer.write(*b)?;
+        }
+        writer.finish()?;
+        Ok(output)
+    }
+
+    #[test]
+    fn read() {
+        let input = [0x41, 0x80, 0xe9, 0xff];
+        let output: Vec<u8> = decode(&input, CodePage::Latin1)
+            .into_iter()
+            .map(|b| b.unwrap())
+            .collect();
+        assert_eq!("A\u{80}éÿ".as_bytes(), output);
+        let output = decode(&input, CodePage::Windows1252);
+        let output: Vec<u8> = output.into_iter().map(|b| b.unwrap()).collect();
+        assert_eq!("A€éÿ".as_bytes(), output);
+        let output = decode(&[0x81], CodePage::Windows1252);
+        assert!(matches!(output[..], [Err(InError::InvalidByte('\u{81}'))]));
+    }
+
+    #[test]
+    fn write() {
+        let input = "A€éÿ".as_bytes();
+        assert_eq!(
+            [0x41, 0x80, 0xe9, 0xff].as_slice(),
+            encode(input, CodePage::Windows1252, Invalid::Strict).unwrap()
+        );
+        assert!(matches!(
+            encode(input, CodePage::Latin1, Invalid::Strict),
+            Err(OutError::InvalidByte(0xe2))
+        ));
+        assert_eq!(
+            b"A?\xe9\xff?".as_slice(),
+            encode(
+                b"A\xe2\x82\xac\xc3\xa9\xc3\xbf\xff",
+                CodePage::Latin1,
+                Invalid::Replace
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            b"\\xe2\\x82\\xac".as_slice(),
+            encode("€".as_bytes(), CodePage::Latin1, Invalid::Escape).unwrap()
+        );
+        assert!(matches!(
+            encode("\u{80}".as_bytes(), CodePage::Windows1252, Invalid::Strict),
+            Err(OutError::InvalidByte(0xc2))
+        ));
+    }
+
+    #[test]
+    fn ebcdic() {
+        let input = [0xc8, 0x85, 0x93, 0x93, 0x96, 0x40, 0x4a, 0x5a, 0xba, 0xbb];
+        let text: Vec<u8> = decode(&input, CodePage::Cp037)
+            .into_iter()
+            .map(|b| b.unwrap())
+            .collect();
+        assert_eq!("Hello ¢![]".as_bytes(), text);
+        let text: Vec<u8> = decode(&input, CodePage::Cp500)
+            .into_iter()
+            .map(|b| b.unwrap())
+            .collect();
+        assert_eq!("Hello []¬|".as_bytes(), text);
+        assert_eq!(
+            [0xad, 0xbd, 0x5f, 0xb0].as_slice(),
+            encode("[]^¬".as_bytes(), CodePage::Cp1047, Invalid::Strict).unwrap()
+        );
+        assert_eq!(
+            [0xc1, 0x6f].as_slice(),
+            encode("A€".as_bytes(), CodePage::Cp037, Invalid::Replace).unwrap()
+        );
+    }
+
+    #[test]
+    fn records() {
+        let input = [0xc1, 0xc2, 0xc3, 0xc4, 0xc5];
+        let text: Vec<u8> = Reader::with_records(input.as_slice(), CodePage::Cp037, 2)
+            .map(|b| b.unwrap())
+            .collect();
+        assert_eq!(b"AB\nCD\nE".as_slice(), text);
+        let mut output = vec![];
+        let mut writer = Writer::with_options(&mut output, CodePage::Cp037, Invalid::Strict, 3);
+        for b in b"AB\n\nABCD\nABC\nA" {
+            writer.write(*b).unwrap();
+        }
+        writer.finish().unwrap();
+        assert_eq!(
+            [
+                0xc1, 0xc2, 0x40, 0x40, 0x40, 0x40, 0xc1, 0xc2, 0xc3, 0xc4, 0x40, 0x40, 0xc1, 0xc2,
+                0xc3, 0xc1, 0x40, 0x40
+            ]
+            .as_slice(),
+            output
+        );
+    }
+
+    #[test]
+    fn roundtrip() {
+        let code_pages = [
+            CodePage::Latin1,
+            CodePage::Windows1252,
+            CodePage::Cp037,
+            CodePage::Cp500,
+            CodePage::Cp1047,
+        ];
+        for code_page in code_pages {
+            let input: Vec<u8> = (0..=255u8)
+                .filter(|&b| code_page.decode(b).is_some())
+                .collect();
+            let text: Vec<u8> = decode(&input, code_page)
+                .into_iter()
+                .map(|b| b.unwrap())
+                .collect();
+            assert_eq!(input, encode(&text, code_page, Invalid::Strict).unwrap());
+        }
+    }
+}
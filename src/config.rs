@@ -1,14 +1,35 @@
 use bread_cli as bread;
 use std::fmt::Display;
 
+use bread::array;
 use bread::ascii;
+use bread::ascii85;
 use bread::base;
+use bread::base32;
+use bread::base64;
 use bread::binary;
+use bread::byte_reader::ByteReader;
 use bread::byte_writer::ByteWriter;
-use bread::error::*;
+use bread::codepage;
+use bread::error::OnError;
+use bread::escaped;
+use bread::float;
 use bread::hexadecimal;
+use bread::hexdump;
+use bread::ihex;
+use bread::integer;
+use bread::leb128;
+use bread::percent;
+use bread::quoted_printable;
+use bread::radix;
 use bread::raw;
-use clap::Parser;
+use bread::srec;
+use bread::text;
+use bread::unicode;
+use bread::utf8;
+use bread::uuencode;
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser};
 
 #[derive(Debug, Parser)]
 #[command(author, version, about)]
@@ -20,9 +41,142 @@ struct Args {
     #[arg(short, long, value_parser = Mode::parse, default_value_t = Mode::Ascii, long_help = Mode::LONG_HELP)]
     /// output format
     output: Mode,
+
+    #[arg(short, long)]
+    /// bytes per line in hex dump (default: 16), array (default: 12), Intel HEX and S-record output
+    /// (default: 16)
+    cols: Option<usize>,
+
+    #[arg(short, long)]
+    /// bytes per group in hex dump output (default: 2 for xxd, 8 for hexdump)
+    group: Option<usize>,
+
+    #[arg(long, value_parser = parse_address, default_value = "0")]
+    /// address of the first byte in Intel HEX and S-record output, decimal or hexadecimal with '0x' prefix
+    address: u32,
+
+    #[arg(long, value_parser = parse_byte, default_value = "ff", value_name = "XX")]
+    /// hexadecimal byte filling gaps between records in Intel HEX and S-record input
+    fill: u8,
+
+    #[arg(long, default_value_t = 0, value_name = "LEN")]
+    /// fixed record length in code page input and output (g.e. 80): a newline after every record
+    /// on input, every line padded with spaces to a record on output; no records if 0
+    record: usize,
+
+    #[arg(long)]
+    /// identifier in array output (default: 'data', 'DATA' for Rust) and file name in uuencode and
+    /// xxencode output (default: 'data')
+    name: Option<String>,
+
+    #[arg(long)]
+    /// byte order mark in utf16le, utf16be, utf32le and utf32be output (always written in utf16
+    /// and utf32 output)
+    bom: bool,
+
+    #[arg(long)]
+    /// hexadecimal values in integer and LEB128 input and output (decimal by default)
+    hex: bool,
+
+    #[arg(long, default_value_t = String::new())]
+    /// separator between bytes in bin, hex and base N output and between integer or float values
+    /// (g.e. ' ', ',' or ':')
+    sep: String,
+
+    #[arg(long, default_value_t = String::new())]
+    /// prefix of every byte in bin, hex and base N output and of integer values (g.e. '0x')
+    prefix: String,
+
+    #[arg(long)]
+    /// upper case digits in hex, base N and integer output
+    upper: bool,
+
+    #[arg(long, default_value_t = 0)]
+    /// bytes per line in bin, hex and base N output (values per line in integer and float
+    /// output), no line breaks if 0
+    wrap: usize,
+
+    #[arg(long)]
+    /// terminating newline in bin, hex, base N, integer and float output
+    newline: bool,
+
+    #[arg(long, num_args = 0..=1, default_missing_value = ",:;", value_name = "SEPARATORS")]
+    /// lenient bin, hex and base N input: tokens split by whitespace, SEPARATORS (default: ",:;")
+    /// or brackets, optionally prefixed (g.e. '0x', '\x' or '0b')
+    lenient: Option<String>,
+
+    #[arg(long, value_parser = parse_on_error, default_value = "fail", value_name = "POLICY")]
+    /// invalid input policy: fail, skip or replace:XX (write hexadecimal byte XX instead)
+    on_error: OnError,
+
+    #[arg(long, value_parser = parse_representation, default_value = "strict", value_name = "REPR")]
+    /// control and non-ASCII bytes in ascii output: strict (control bytes unchanged, non-ASCII
+    /// bytes invalid), escape (g.e. '\x1b'), caret (g.e. '^['), pictures (g.e. '␛') or
+    /// replace:C (character C instead)
+    nonprint: ascii::Representation,
+
+    #[arg(long, value_parser = parse_invalid, default_value = "strict", value_name = "REPR")]
+    /// invalid sequences in utf8, codepoints, UTF-16/32 and code page output, as well as
+    /// characters not in the code page: strict (invalid), replace (g.e. '�', '?' in code page
+    /// output) or escape (g.e. '\xff')
+    invalid: utf8::Invalid,
+}
+
+fn parse_representation(arg: &str) -> Result<ascii::Representation, String> {
+    match arg {
+        "strict" => Ok(ascii::Representation::Strict),
+        "escape" => Ok(ascii::Representation::Escape),
+        "caret" => Ok(ascii::Representation::Caret),
+        "pictures" => Ok(ascii::Representation::ControlPictures),
+        _ => {
+            let mut chars = arg.strip_prefix("replace:").unwrap_or_default().chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Ok(ascii::Representation::Replace(c)),
+                _ => Err("allowed representations: strict, escape, caret, pictures or replace:C where C is a single character".to_string()),
+            }
+        }
+    }
+}
+
+fn parse_invalid(arg: &str) -> Result<utf8::Invalid, String> {
+    match arg {
+        "strict" => Ok(utf8::Invalid::Strict),
+        "replace" => Ok(utf8::Invalid::Replace),
+        "escape" => Ok(utf8::Invalid::Escape),
+        _ => Err("allowed representations: strict, replace or escape".to_string()),
+    }
+}
+
+fn parse_address(arg: &str) -> Result<u32, String> {
+    match arg.strip_prefix("0x").or(arg.strip_prefix("0X")) {
+        Some(hex) => u32::from_str_radix(hex, 16),
+        None => arg.parse(),
+    }
+    .map_err(|e| e.to_string())
+}
+
+fn parse_byte(arg: &str) -> Result<u8, String> {
+    u8::from_str_radix(arg, 16).map_err(|e| e.to_string())
+}
+
+fn parse_on_error(arg: &str) -> Result<OnError, String> {
+    match arg {
+        "fail" => Ok(OnError::Fail),
+        "skip" => Ok(OnError::Skip),
+        _ => match arg
+            .strip_prefix("replace:")
+            .map(|b| u8::from_str_radix(b, 16))
+        {
+            Some(Ok(byte)) => Ok(OnError::Replace(byte)),
+            _ => Err(
+                "allowed policies: fail, skip or replace:XX where XX is a hexadecimal byte value"
+                    .to_string(),
+            ),
+        },
+    }
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
 enum Mode {
     /// raw byte
     Raw,
@@ -32,8 +186,74 @@ enum Mode {
     Hex,
     /// ASCII characters (g.e. '!')
     Ascii,
+    /// UTF-8 text (g.e. 'é')
+    Utf8,
+    /// Unicode code points of UTF-8 text (g.e. 'U+00E9')
+    CodePoints,
+    /// UTF-16 or UTF-32 text, with the provided byte order or detected by byte order mark
+    Unicode(unicode::Form, Option<integer::Endianness>),
+    /// single byte code page text, ASCII based or EBCDIC (g.e. 0xe9 for 'é' in Latin-1)
+    CodePage(codepage::CodePage),
+    /// string literal content with backslash escapes (g.e. 'a\n\xff')
+    Escaped,
+    /// percent-encoding (g.e. 'a%20b')
+    Percent(percent::Set),
+    /// quoted-printable (g.e. 'caf=C3=A9')
+    QuotedPrintable,
+    /// uuencode or xxencode (g.e. 'begin 644 data')
+    Uuencode(uuencode::Variant),
     /// numeric base (2 to 36)
     Base(u8),
+    /// base32 with RFC 4648 alphabet (g.e. 'MY======')
+    Base32,
+    /// base32 with RFC 4648 extended hex alphabet (g.e. 'CO======')
+    Base32Hex,
+    /// base32 with Crockford's alphabet (g.e. 'CR')
+    Base32Crockford,
+    /// base64 with standard alphabet (g.e. 'TWE=')
+    Base64,
+    /// base64 with URL and filename safe alphabet (g.e. '-_8=')
+    Base64Url,
+    /// base58 with Bitcoin alphabet, whole stream as a single number (g.e. '11233QC4')
+    Base58,
+    /// base58 with Flickr alphabet, whole stream as a single number (g.e. '11233pc4')
+    Base58Flickr,
+    /// base62, whole stream as a single number (g.e. '18OWF')
+    Base62,
+    /// custom alphabet, whole stream as a single number
+    Alphabet(String),
+    /// Ascii85 (g.e. '9jqo^')
+    Ascii85,
+    /// Z85 (g.e. 'HelloWorld')
+    Z85,
+    /// xxd hex dump (g.e. '00000000: 6865 6c6c 6f0a  hello.')
+    Xxd,
+    /// hexdump -C canonical hex dump (g.e. '00000000  68 65 6c 6c 6f 0a  |hello.|')
+    HexDump,
+    /// Intel HEX (g.e. ':0300000041424337')
+    IHex,
+    /// Motorola S-record with 16 bits addresses (g.e. 'S106000041424333')
+    S19,
+    /// Motorola S-record with 24 bits addresses (g.e. 'S20700000041424332')
+    S28,
+    /// Motorola S-record with 32 bits addresses (g.e. 'S3080000000041424331')
+    S37,
+    /// C array (g.e. 'unsigned char data[] = { 0x00, 0xff, };')
+    C,
+    /// Rust array (g.e. 'const DATA: [u8; 2] = [ 0x00, 0xff, ];')
+    Rust,
+    /// Python bytes (g.e. 'data = bytes([ 0x00, 0xff, ])')
+    Python,
+    /// Go slice (g.e. 'var data = []byte{ 0x00, 0xff, }')
+    Go,
+    /// Java array (g.e. 'byte[] data = { 0x00, (byte) 0xff, };')
+    Java,
+    /// integer values (g.e. '513 65535' for 16 bits unsigned little endian)
+    Integer(integer::Integer),
+    /// IEEE 754 floating point values (g.e. '1.5 -inf' for 32 bits little endian)
+    Float(float::Float),
+    /// LEB128 coded integer values (g.e. '300' for 0xac 0x02)
+    Leb128(leb128::Encoding),
 }
 
 impl Mode {
@@ -42,6 +262,49 @@ impl Mode {
 - bin:   binary representation (g.e. '00001101')
 - hex:   hexadecimal representation (g.e. 'a4')
 - ascii: ASCII characters (g.e. '!')
+- utf8:  UTF-8 text, validated (g.e. 'é')
+- codepoints: Unicode code points of UTF-8 text (g.e. 'U+00E9')
+- utf16, utf16le, utf16be, utf32, utf32le, utf32be: UTF-16 or UTF-32 text, little (le) or big
+         (be) endian, as UTF-8 text; byte order mark skipped on input, detected by utf16 and
+         utf32 (big endian if missing), written by utf16 and utf32 (big endian) on output
+- latin1, cp1252: ISO-8859-1 or Windows-1252 text, as UTF-8 text (g.e. 0x80 for '€' in cp1252)
+- cp037, cp500, cp1047: EBCDIC US/Canada, International or Open Systems text, as UTF-8 text
+         (g.e. 0xc1 for 'A'), optionally in fixed length records (see --record)
+- esc:   string literal content with C, Rust or Python backslash escapes, optionally quoted
+         (g.e. 'a\n\xff'); input only
+- url, component, form: percent-encoding (g.e. '%E2%9C%93'), escaping all but RFC 3986 path
+         characters, unreserved characters (for a single URL component, g.e. a query value) or HTML
+         form characters (with space as '+', also on input)
+- qp:    quoted-printable, lines of up to 76 characters (g.e. 'caf=C3=A9')
+- uu, xx: uuencode or xxencode, framed by 'begin' and 'end' lines, 45 bytes per line (g.e. for uu
+         '#0V%T' for 'Cat'); on input, lines before 'begin' are skipped
+- b32:   base32 with RFC 4648 alphabet (g.e. 'MY======')
+- b32h:  base32 with RFC 4648 extended hex alphabet (g.e. 'CO======')
+- b32c:  base32 with Crockford's alphabet (g.e. 'CR')
+- b64:   base64 with standard alphabet (g.e. 'TWE=')
+- b64u:  base64 with URL and filename safe alphabet (g.e. '-_8=')
+- b58:   base58 with Bitcoin alphabet, whole stream as a single number (g.e. '11233QC4')
+- b58f:  base58 with Flickr alphabet, whole stream as a single number (g.e. '11233pc4')
+- b62:   base62, whole stream as a single number (g.e. '18OWF')
+- a85:   Ascii85, optionally enclosed in '<~' '~>' on input (g.e. '9jqo^')
+- z85:   Z85 (g.e. 'HelloWorld')
+- xxd:   xxd hex dump (g.e. '00000000: 6865 6c6c 6f0a  hello.')
+- hexdump: hexdump -C canonical hex dump (g.e. '00000000  68 65 6c 6c 6f 0a  |hello.|')
+- ihex:  Intel HEX; on input, the memory image from the lowest address (g.e. ':0300000041424337')
+- s19, s28, s37: Motorola S-record with 16, 24 or 32 bits addresses; on input, the memory image
+         from the lowest address, any of them (g.e. for s19 'S106000041424333')
+- c, rust, py, go, java: array literal in C, Rust, Python, Go or Java (g.e. for C
+         'unsigned char data[] = { 0x00, 0xff, };'); any of them on input
+- u8, i8, uNle, uNbe, iNle, iNbe with N in 16, 32, 64: unsigned (u) or signed (i) integers of N
+         bits, little (le) or big (be) endian, in decimal or hexadecimal (g.e. for u16le
+         '513 65535')
+- f16le, f16be, f32le, f32be, f64le, f64be: IEEE 754 half, single or double precision floating
+         point values, little (le) or big (be) endian (g.e. for f32le '1.5 -inf NaN')
+- uleb128 (or varint), sleb128, zigzag: unsigned, signed or zigzag (protobuf sint) LEB128 variable
+         length coded 64 bits integers, in decimal or hexadecimal (g.e. for uleb128 '300' coded
+         as 0xac 0x02)
+- alphabet:SYMBOLS: whole stream as a single number written with the provided symbols, the first
+         one being the zero digit (g.e. 'alphabet:01234567' for octal)
 - N:     base N representation (note: make sure to provide required number of digits per each byte, pad with heading 0s) "#;
     fn parse(arg: &str) -> Result<Self, String> {
         if let Ok(base) = arg.parse::<u8>() {
@@ -50,19 +313,106 @@ impl Mode {
             } else {
                 Err("base must be in [2,36]".to_string())
             }
+        } else if let Some(mode) = Self::number(arg) {
+            Ok(mode)
+        } else if let Some(symbols) = arg.strip_prefix("alphabet:") {
+            if radix::Alphabet::new(symbols).is_some() {
+                Ok(Mode::Alphabet(symbols.to_string()))
+            } else {
+                Err(
+                    "alphabet must have at least 2 unique printable ASCII symbols (no spaces)"
+                        .to_string(),
+                )
+            }
         } else {
             match arg {
                 "raw" | "r" => Ok(Mode::Raw),
                 "bin" | "b" => Ok(Mode::Bin),
                 "hex" | "h" => Ok(Mode::Hex),
                 "ascii" | "a" => Ok(Mode::Ascii),
+                "utf8" | "utf-8" => Ok(Mode::Utf8),
+                "codepoints" => Ok(Mode::CodePoints),
+                "utf16" | "utf-16" => Ok(Mode::Unicode(unicode::Form::Utf16, None)),
+                "utf16le" => Ok(Mode::Unicode(
+                    unicode::Form::Utf16,
+                    Some(integer::Endianness::Little),
+                )),
+                "utf16be" => Ok(Mode::Unicode(
+                    unicode::Form::Utf16,
+                    Some(integer::Endianness::Big),
+                )),
+                "utf32" | "utf-32" => Ok(Mode::Unicode(unicode::Form::Utf32, None)),
+                "utf32le" => Ok(Mode::Unicode(
+                    unicode::Form::Utf32,
+                    Some(integer::Endianness::Little),
+                )),
+                "utf32be" => Ok(Mode::Unicode(
+                    unicode::Form::Utf32,
+                    Some(integer::Endianness::Big),
+                )),
+                "latin1" | "iso-8859-1" => Ok(Mode::CodePage(codepage::CodePage::Latin1)),
+                "cp1252" | "windows-1252" => Ok(Mode::CodePage(codepage::CodePage::Windows1252)),
+                "cp037" | "ebcdic" => Ok(Mode::CodePage(codepage::CodePage::Cp037)),
+                "cp500" => Ok(Mode::CodePage(codepage::CodePage::Cp500)),
+                "cp1047" => Ok(Mode::CodePage(codepage::CodePage::Cp1047)),
+                "escaped" | "esc" => Ok(Mode::Escaped),
+                "url" | "percent" => Ok(Mode::Percent(percent::Set::Path)),
+                "component" => Ok(Mode::Percent(percent::Set::Component)),
+                "form" => Ok(Mode::Percent(percent::Set::Form)),
+                "qp" | "quoted-printable" => Ok(Mode::QuotedPrintable),
+                "uu" | "uuencode" => Ok(Mode::Uuencode(uuencode::Variant::Uuencode)),
+                "xx" | "xxencode" => Ok(Mode::Uuencode(uuencode::Variant::Xxencode)),
+                "base32" | "b32" => Ok(Mode::Base32),
+                "base32hex" | "b32h" => Ok(Mode::Base32Hex),
+                "crockford" | "b32c" => Ok(Mode::Base32Crockford),
+                "base64" | "b64" => Ok(Mode::Base64),
+                "base64url" | "b64u" => Ok(Mode::Base64Url),
+                "base58" | "b58" => Ok(Mode::Base58),
+                "base58flickr" | "b58f" => Ok(Mode::Base58Flickr),
+                "base62" | "b62" => Ok(Mode::Base62),
+                "ascii85" | "a85" => Ok(Mode::Ascii85),
+                "z85" => Ok(Mode::Z85),
+                "xxd" => Ok(Mode::Xxd),
+                "hexdump" => Ok(Mode::HexDump),
+                "ihex" => Ok(Mode::IHex),
+                "s19" | "srec" => Ok(Mode::S19),
+                "s28" => Ok(Mode::S28),
+                "s37" => Ok(Mode::S37),
+                "c" => Ok(Mode::C),
+                "rust" | "rs" => Ok(Mode::Rust),
+                "python" | "py" => Ok(Mode::Python),
+                "go" => Ok(Mode::Go),
+                "java" => Ok(Mode::Java),
+                "uleb128" | "varint" => Ok(Mode::Leb128(leb128::Encoding::Unsigned)),
+                "sleb128" => Ok(Mode::Leb128(leb128::Encoding::Signed)),
+                "zigzag" => Ok(Mode::Leb128(leb128::Encoding::ZigZag)),
                 _ => Err(
-                    "allowed modes: raw, bin, hex, ascii or N where N is a numeric base in [2,36]"
+                    "allowed modes: raw, bin, hex, ascii, utf8, codepoints, utf16, utf16le, utf16be, utf32, utf32le, utf32be, latin1, cp1252, cp037, cp500, cp1047, esc, url, component, form, qp, uu, xx, b32, b32h, b32c, b64, b64u, b58, b58f, b62, a85, z85, xxd, hexdump, ihex, s19, s28, s37, c, rust, py, go, java, u8, i8, u16le, u16be, i16le, i16be, u32le, u32be, i32le, i32be, u64le, u64be, i64le, i64be, f16le, f16be, f32le, f32be, f64le, f64be, uleb128, varint, sleb128, zigzag, alphabet:SYMBOLS or N where N is a numeric base in [2,36]"
                         .to_string(),
                 ),
             }
         }
     }
+
+    /// Parses integer and floating point modes (g.e. 'u16le' or 'f32be')
+    fn number(arg: &str) -> Option<Self> {
+        let (kind, rest) = arg.split_at_checked(1)?;
+        let (bits, endianness) = match rest {
+            "8" => ("8", integer::Endianness::Little),
+            _ => match rest.split_at_checked(rest.len().checked_sub(2)?)? {
+                (bits, "le") if bits != "8" => (bits, integer::Endianness::Little),
+                (bits, "be") if bits != "8" => (bits, integer::Endianness::Big),
+                _ => return None,
+            },
+        };
+        let bits = bits.parse().ok()?;
+        match kind {
+            "u" => integer::Integer::new(bits, false, endianness).map(Mode::Integer),
+            "i" => integer::Integer::new(bits, true, endianness).map(Mode::Integer),
+            "f" => float::Float::new(bits, endianness).map(Mode::Float),
+            _ => None,
+        }
+    }
 }
 
 impl Display for Mode {
@@ -75,55 +425,405 @@ impl Display for Mode {
                 Mode::Bin => "bin".to_string(),
                 Mode::Hex => "hex".to_string(),
                 Mode::Ascii => "ascii".to_string(),
+                Mode::Utf8 => "utf8".to_string(),
+                Mode::CodePoints => "codepoints".to_string(),
+                Mode::Unicode(form, endianness) => {
+                    let form = match form {
+                        unicode::Form::Utf16 => "utf16",
+                        unicode::Form::Utf32 => "utf32",
+                    };
+                    let endianness = match endianness {
+                        None => "",
+                        Some(integer::Endianness::Little) => "le",
+                        Some(integer::Endianness::Big) => "be",
+                    };
+                    format!("{form}{endianness}")
+                }
+                Mode::CodePage(codepage::CodePage::Latin1) => "latin1".to_string(),
+                Mode::CodePage(codepage::CodePage::Windows1252) => "cp1252".to_string(),
+                Mode::CodePage(codepage::CodePage::Cp037) => "cp037".to_string(),
+                Mode::CodePage(codepage::CodePage::Cp500) => "cp500".to_string(),
+                Mode::CodePage(codepage::CodePage::Cp1047) => "cp1047".to_string(),
+                Mode::Escaped => "escaped".to_string(),
+                Mode::Percent(percent::Set::Path) => "url".to_string(),
+                Mode::Percent(percent::Set::Component) => "component".to_string(),
+                Mode::Percent(percent::Set::Form) => "form".to_string(),
+                Mode::QuotedPrintable => "qp".to_string(),
+                Mode::Uuencode(uuencode::Variant::Uuencode) => "uuencode".to_string(),
+                Mode::Uuencode(uuencode::Variant::Xxencode) => "xxencode".to_string(),
                 Mode::Base(b) => format!("base {b}"),
+                Mode::Base32 => "base32".to_string(),
+                Mode::Base32Hex => "base32hex".to_string(),
+                Mode::Base32Crockford => "crockford".to_string(),
+                Mode::Base64 => "base64".to_string(),
+                Mode::Base64Url => "base64url".to_string(),
+                Mode::Base58 => "base58".to_string(),
+                Mode::Base58Flickr => "base58flickr".to_string(),
+                Mode::Base62 => "base62".to_string(),
+                Mode::Alphabet(symbols) => format!("alphabet:{symbols}"),
+                Mode::Ascii85 => "ascii85".to_string(),
+                Mode::Z85 => "z85".to_string(),
+                Mode::Xxd => "xxd".to_string(),
+                Mode::HexDump => "hexdump".to_string(),
+                Mode::IHex => "ihex".to_string(),
+                Mode::S19 => "s19".to_string(),
+                Mode::S28 => "s28".to_string(),
+                Mode::S37 => "s37".to_string(),
+                Mode::C => "c".to_string(),
+                Mode::Rust => "rust".to_string(),
+                Mode::Python => "python".to_string(),
+                Mode::Go => "go".to_string(),
+                Mode::Java => "java".to_string(),
+                Mode::Integer(integer) => {
+                    let sign = if integer.signed() { "i" } else { "u" };
+                    let endianness = match (integer.bits(), integer.endianness()) {
+                        (8, _) => "",
+                        (_, integer::Endianness::Little) => "le",
+                        (_, integer::Endianness::Big) => "be",
+                    };
+                    format!("{sign}{}{endianness}", integer.bits())
+                }
+                Mode::Float(float) => {
+                    let endianness = match float.endianness() {
+                        integer::Endianness::Little => "le",
+                        integer::Endianness::Big => "be",
+                    };
+                    format!("f{}{endianness}", float.bits())
+                }
+                Mode::Leb128(leb128::Encoding::Unsigned) => "uleb128".to_string(),
+                Mode::Leb128(leb128::Encoding::Signed) => "sleb128".to_string(),
+                Mode::Leb128(leb128::Encoding::ZigZag) => "zigzag".to_string(),
             }
         )
     }
 }
 
 pub struct Config {
-    reader: Box<dyn Iterator<Item = Result<u8, InError>>>,
+    reader: Box<dyn ByteReader>,
     writer: Box<dyn ByteWriter>,
+    on_error: OnError,
 }
 
-pub type IO = (
-    Box<dyn Iterator<Item = Result<u8, InError>>>,
-    Box<dyn ByteWriter>,
-);
+pub type IO = (Box<dyn ByteReader>, Box<dyn ByteWriter>, OnError);
 
 impl Config {
     pub fn new() -> Option<Self> {
         let args = Args::parse();
 
         Some(Config {
-            reader: match args.input {
+            on_error: args.on_error,
+            reader: match &args.input {
                 Mode::Raw => Box::new(raw::Reader::new(std::io::stdin())),
-                Mode::Bin => Box::new(binary::Reader::new(std::io::stdin())),
-                Mode::Hex => Box::new(hexadecimal::Reader::new(std::io::stdin())),
+                Mode::Bin => Self::binary(&args),
+                Mode::Hex => Self::hexadecimal(&args),
                 Mode::Ascii => Box::new(ascii::Reader::new(std::io::stdin())),
+                Mode::Utf8 => Box::new(utf8::Reader::new(std::io::stdin())),
+                Mode::CodePoints => Box::new(utf8::Reader::with_style(
+                    std::io::stdin(),
+                    utf8::Style::CodePoints,
+                )),
+                Mode::Unicode(form, endianness) => {
+                    Box::new(unicode::Reader::new(std::io::stdin(), *form, *endianness))
+                }
+                Mode::CodePage(code_page) => Box::new(codepage::Reader::with_records(
+                    std::io::stdin(),
+                    *code_page,
+                    args.record,
+                )),
+                Mode::Escaped => Box::new(escaped::Reader::new(std::io::stdin())),
+                Mode::Percent(set) => Box::new(percent::Reader::new(std::io::stdin(), *set)),
+                Mode::QuotedPrintable => Box::new(quoted_printable::Reader::new(std::io::stdin())),
+                Mode::Uuencode(variant) => {
+                    Box::new(uuencode::Reader::new(std::io::stdin(), *variant))
+                }
                 Mode::Base(b) => match b {
-                    2 => Box::new(binary::Reader::new(std::io::stdin())),
-                    16 => Box::new(hexadecimal::Reader::new(std::io::stdin())),
-                    _ => Box::new(base::Reader::new(std::io::stdin(), b)),
+                    2 => Self::binary(&args),
+                    16 => Self::hexadecimal(&args),
+                    _ => match Self::lenient(&args, text::Lenient::default()) {
+                        Some(lenient) => {
+                            Box::new(base::Reader::lenient(std::io::stdin(), *b, lenient))
+                        }
+                        None => Box::new(base::Reader::new(std::io::stdin(), *b)),
+                    },
                 },
+                Mode::Base32 => Box::new(base32::Reader::new(
+                    std::io::stdin(),
+                    base32::Alphabet::Standard,
+                )),
+                Mode::Base32Hex => {
+                    Box::new(base32::Reader::new(std::io::stdin(), base32::Alphabet::Hex))
+                }
+                Mode::Base32Crockford => Box::new(base32::Reader::new(
+                    std::io::stdin(),
+                    base32::Alphabet::Crockford,
+                )),
+                Mode::Base64 => Box::new(base64::Reader::new(
+                    std::io::stdin(),
+                    base64::Alphabet::Standard,
+                )),
+                Mode::Base64Url => Box::new(base64::Reader::new(
+                    std::io::stdin(),
+                    base64::Alphabet::UrlSafe,
+                )),
+                Mode::Base58 => Box::new(radix::Reader::new(
+                    std::io::stdin(),
+                    radix::Alphabet::base58(),
+                )),
+                Mode::Base58Flickr => Box::new(radix::Reader::new(
+                    std::io::stdin(),
+                    radix::Alphabet::base58_flickr(),
+                )),
+                Mode::Base62 => Box::new(radix::Reader::new(
+                    std::io::stdin(),
+                    radix::Alphabet::base62(),
+                )),
+                Mode::Alphabet(symbols) => Box::new(radix::Reader::new(
+                    std::io::stdin(),
+                    radix::Alphabet::new(symbols).unwrap(),
+                )),
+                Mode::Ascii85 => Box::new(ascii85::Reader::new(
+                    std::io::stdin(),
+                    ascii85::Variant::Ascii85,
+                )),
+                Mode::Z85 => Box::new(ascii85::Reader::new(
+                    std::io::stdin(),
+                    ascii85::Variant::Z85,
+                )),
+                Mode::Xxd | Mode::HexDump => Box::new(hexdump::Reader::new(std::io::stdin())),
+                Mode::IHex => Box::new(ihex::Reader::with_fill(std::io::stdin(), args.fill)),
+                Mode::S19 | Mode::S28 | Mode::S37 => {
+                    Box::new(srec::Reader::with_fill(std::io::stdin(), args.fill))
+                }
+                Mode::C | Mode::Rust | Mode::Python | Mode::Go | Mode::Java => {
+                    Box::new(array::Reader::new(std::io::stdin()))
+                }
+                Mode::Integer(integer) => Box::new(integer::Reader::new(
+                    std::io::stdin(),
+                    *integer,
+                    Self::notation(&args),
+                )),
+                Mode::Float(float) => Box::new(float::Reader::new(std::io::stdin(), *float)),
+                Mode::Leb128(encoding) => Box::new(leb128::Reader::new(
+                    std::io::stdin(),
+                    *encoding,
+                    Self::notation(&args),
+                )),
             },
-            writer: match args.output {
+            writer: match &args.output {
                 Mode::Raw => Box::new(raw::Writer::new(std::io::stdout())),
-                Mode::Bin => Box::new(binary::Writer::new(std::io::stdout())),
-                Mode::Hex => Box::new(hexadecimal::Writer::new(std::io::stdout())),
-                Mode::Ascii => Box::new(ascii::Writer::new(std::io::stdout())),
+                Mode::Bin => Box::new(binary::Writer::with_format(
+                    std::io::stdout(),
+                    Self::format(&args),
+                )),
+                Mode::Hex => Box::new(hexadecimal::Writer::with_format(
+                    std::io::stdout(),
+                    Self::format(&args),
+                )),
+                Mode::Escaped => Args::command()
+                    .error(ErrorKind::InvalidValue, "esc is an input only mode")
+                    .exit(),
+                Mode::Percent(set) => Box::new(percent::Writer::new(std::io::stdout(), *set)),
+                Mode::QuotedPrintable => Box::new(quoted_printable::Writer::new(std::io::stdout())),
+                Mode::Uuencode(variant) => Box::new(uuencode::Writer::with_name(
+                    std::io::stdout(),
+                    *variant,
+                    args.name.as_deref().unwrap_or("data"),
+                )),
+                Mode::Ascii => Box::new(ascii::Writer::with_representation(
+                    std::io::stdout(),
+                    args.nonprint,
+                )),
+                Mode::Utf8 => Box::new(utf8::Writer::with_style(
+                    std::io::stdout(),
+                    utf8::Style::Text,
+                    args.invalid,
+                )),
+                Mode::CodePoints => Box::new(utf8::Writer::with_style(
+                    std::io::stdout(),
+                    utf8::Style::CodePoints,
+                    args.invalid,
+                )),
+                Mode::Unicode(form, endianness) => Box::new(unicode::Writer::with_options(
+                    std::io::stdout(),
+                    *form,
+                    endianness.unwrap_or(integer::Endianness::Big),
+                    args.bom || endianness.is_none(),
+                    args.invalid,
+                )),
+                Mode::CodePage(code_page) => Box::new(codepage::Writer::with_options(
+                    std::io::stdout(),
+                    *code_page,
+                    args.invalid,
+                    args.record,
+                )),
                 Mode::Base(b) => match b {
-                    2 => Box::new(binary::Writer::new(std::io::stdout())),
-                    16 => Box::new(hexadecimal::Writer::new(std::io::stdout())),
-                    _ => Box::new(base::Writer::new(std::io::stdout(), b)),
+                    2 => Box::new(binary::Writer::with_format(
+                        std::io::stdout(),
+                        Self::format(&args),
+                    )),
+                    16 => Box::new(hexadecimal::Writer::with_format(
+                        std::io::stdout(),
+                        Self::format(&args),
+                    )),
+                    _ => Box::new(base::Writer::with_format(
+                        std::io::stdout(),
+                        *b,
+                        Self::format(&args),
+                    )),
                 },
+                Mode::Base32 => Box::new(base32::Writer::new(
+                    std::io::stdout(),
+                    base32::Alphabet::Standard,
+                )),
+                Mode::Base32Hex => Box::new(base32::Writer::new(
+                    std::io::stdout(),
+                    base32::Alphabet::Hex,
+                )),
+                Mode::Base32Crockford => Box::new(base32::Writer::new(
+                    std::io::stdout(),
+                    base32::Alphabet::Crockford,
+                )),
+                Mode::Base64 => Box::new(base64::Writer::new(
+                    std::io::stdout(),
+                    base64::Alphabet::Standard,
+                )),
+                Mode::Base64Url => Box::new(base64::Writer::new(
+                    std::io::stdout(),
+                    base64::Alphabet::UrlSafe,
+                )),
+                Mode::Base58 => Box::new(radix::Writer::new(
+                    std::io::stdout(),
+                    radix::Alphabet::base58(),
+                )),
+                Mode::Base58Flickr => Box::new(radix::Writer::new(
+                    std::io::stdout(),
+                    radix::Alphabet::base58_flickr(),
+                )),
+                Mode::Base62 => Box::new(radix::Writer::new(
+                    std::io::stdout(),
+                    radix::Alphabet::base62(),
+                )),
+                Mode::Alphabet(symbols) => Box::new(radix::Writer::new(
+                    std::io::stdout(),
+                    radix::Alphabet::new(symbols).unwrap(),
+                )),
+                Mode::Ascii85 => Box::new(ascii85::Writer::new(
+                    std::io::stdout(),
+                    ascii85::Variant::Ascii85,
+                )),
+                Mode::Z85 => Box::new(ascii85::Writer::new(
+                    std::io::stdout(),
+                    ascii85::Variant::Z85,
+                )),
+                Mode::Xxd => Self::hexdump(&args, hexdump::Style::Xxd),
+                Mode::HexDump => Self::hexdump(&args, hexdump::Style::Canonical),
+                Mode::IHex => Box::new(ihex::Writer::with_layout(
+                    std::io::stdout(),
+                    args.cols.unwrap_or(16),
+                    args.address,
+                )),
+                Mode::S19 => Self::srec(&args, srec::AddressWidth::S19),
+                Mode::S28 => Self::srec(&args, srec::AddressWidth::S28),
+                Mode::S37 => Self::srec(&args, srec::AddressWidth::S37),
+                Mode::C => Self::array(&args, array::Language::C),
+                Mode::Rust => Self::array(&args, array::Language::Rust),
+                Mode::Python => Self::array(&args, array::Language::Python),
+                Mode::Go => Self::array(&args, array::Language::Go),
+                Mode::Java => Self::array(&args, array::Language::Java),
+                Mode::Integer(integer) => Box::new(integer::Writer::with_format(
+                    std::io::stdout(),
+                    *integer,
+                    Self::notation(&args),
+                    Self::format(&args),
+                )),
+                Mode::Float(float) => Box::new(float::Writer::with_format(
+                    std::io::stdout(),
+                    *float,
+                    Self::format(&args),
+                )),
+                Mode::Leb128(encoding) => Box::new(leb128::Writer::with_format(
+                    std::io::stdout(),
+                    *encoding,
+                    Self::notation(&args),
+                    Self::format(&args),
+                )),
             },
         })
     }
+
+    fn lenient(args: &Args, lenient: text::Lenient) -> Option<text::Lenient> {
+        args.lenient.as_ref().map(|separators| text::Lenient {
+            separators: separators.clone(),
+            ..lenient
+        })
+    }
+
+    fn binary(args: &Args) -> Box<dyn ByteReader> {
+        match Self::lenient(args, text::Lenient::binary()) {
+            Some(lenient) => Box::new(binary::Reader::lenient(std::io::stdin(), lenient)),
+            None => Box::new(binary::Reader::new(std::io::stdin())),
+        }
+    }
+
+    fn hexadecimal(args: &Args) -> Box<dyn ByteReader> {
+        match Self::lenient(args, text::Lenient::hexadecimal()) {
+            Some(lenient) => Box::new(hexadecimal::Reader::lenient(std::io::stdin(), lenient)),
+            None => Box::new(hexadecimal::Reader::new(std::io::stdin())),
+        }
+    }
+
+    fn format(args: &Args) -> text::Format {
+        text::Format {
+            separator: args.sep.clone(),
+            prefix: args.prefix.clone(),
+            uppercase: args.upper,
+            wrap: args.wrap,
+            newline: args.newline,
+        }
+    }
+
+    fn notation(args: &Args) -> integer::Notation {
+        if args.hex {
+            integer::Notation::Hexadecimal
+        } else {
+            integer::Notation::Decimal
+        }
+    }
+
+    fn hexdump(args: &Args, style: hexdump::Style) -> Box<dyn ByteWriter> {
+        let group = match style {
+            hexdump::Style::Xxd => 2,
+            hexdump::Style::Canonical => 8,
+        };
+        Box::new(hexdump::Writer::with_layout(
+            std::io::stdout(),
+            style,
+            args.cols.unwrap_or(16),
+            args.group.unwrap_or(group),
+        ))
+    }
+
+    fn array(args: &Args, language: array::Language) -> Box<dyn ByteWriter> {
+        Box::new(array::Writer::with_layout(
+            std::io::stdout(),
+            language,
+            args.name.as_deref().unwrap_or(language.name()),
+            args.cols.unwrap_or(12),
+        ))
+    }
+
+    fn srec(args: &Args, width: srec::AddressWidth) -> Box<dyn ByteWriter> {
+        Box::new(srec::Writer::with_layout(
+            std::io::stdout(),
+            width,
+            args.cols.unwrap_or(16),
+            args.address,
+        ))
+    }
 }
 
 impl From<Config> for IO {
     fn from(config: Config) -> Self {
-        (config.reader, config.writer)
+        (config.reader, config.writer, config.on_error)
     }
 }
@@ -22,6 +22,35 @@ pub enum ErrorType<Byte> {
     /// Depending on output format, not all possible byte values can be represented; f.e. in case
     /// of ASCII format only byte values less than 128 are valid.
     InvalidByte(Byte),
+    /// Invalid line
+    ///
+    /// For line oriented formats (f.e. hex dumps), the line with the provided number (starting
+    /// from 1) does not follow the expected format
+    InvalidLine(usize),
+    /// Invalid checksum
+    ///
+    /// For record oriented formats (f.e. Intel HEX), the checksum of the record at the line with
+    /// the provided number (starting from 1) does not match its content
+    InvalidChecksum(usize),
+    /// Invalid address
+    ///
+    /// For record oriented formats (f.e. Intel HEX), the address of the record at the line with
+    /// the provided number (starting from 1) overlaps previous data or can not be represented
+    InvalidAddress(usize),
+    /// Value out of range
+    ///
+    /// For formats coding numbers wider than a byte (f.e. 16 bits integers), the number read can
+    /// not be represented by the expected type
+    OutOfRange,
+    /// Overlong encoding
+    ///
+    /// For variable length encodings (f.e. LEB128), the value is coded by more bytes than needed
+    Overlong,
+    /// Truncated UTF-8 sequence
+    ///
+    /// For text formats, a UTF-8 lead byte is followed by fewer continuation bytes than expected
+    /// by the provided number, before the next character or the end of the bytes to write
+    Truncated { bytes: usize, expected: usize },
 }
 
 /// Input error
@@ -37,6 +66,118 @@ pub type InError = ErrorType<char>;
 /// [ErrorType]: ErrorType
 pub type OutError = ErrorType<u8>;
 
+impl std::fmt::Display for InError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ErrorType::StdIO(e) => write!(f, "input I/O error: {e}"),
+            ErrorType::ShortIO { bytes, expected } => write!(
+                f,
+                "truncated input: {bytes} characters read, {expected} expected"
+            ),
+            ErrorType::InvalidByte(c) => write!(f, "invalid input character {c:?}"),
+            ErrorType::InvalidLine(line) => write!(f, "invalid input line {line}"),
+            ErrorType::InvalidChecksum(line) => write!(f, "invalid checksum at input line {line}"),
+            ErrorType::InvalidAddress(line) => write!(f, "invalid address at input line {line}"),
+            ErrorType::OutOfRange => write!(f, "input value out of range"),
+            ErrorType::Overlong => write!(f, "overlong input encoding"),
+            ErrorType::Truncated { bytes, expected } => write!(
+                f,
+                "truncated UTF-8 input sequence: {bytes} bytes read, {expected} expected"
+            ),
+        }
+    }
+}
+
+impl std::fmt::Display for OutError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ErrorType::StdIO(e) => write!(f, "output I/O error: {e}"),
+            ErrorType::ShortIO { bytes, expected } => {
+                write!(f, "short write: {bytes} bytes written, {expected} expected")
+            }
+            ErrorType::InvalidByte(b) => {
+                write!(
+                    f,
+                    "byte value 0x{b:02x} can not be written in the output format"
+                )
+            }
+            ErrorType::InvalidLine(line) => write!(f, "invalid output line {line}"),
+            ErrorType::InvalidChecksum(line) => write!(f, "invalid checksum at output line {line}"),
+            ErrorType::InvalidAddress(line) => {
+                write!(f, "address out of range at output line {line}")
+            }
+            ErrorType::OutOfRange => write!(f, "output value out of range"),
+            ErrorType::Overlong => write!(f, "overlong encoding of output value"),
+            ErrorType::Truncated { bytes, expected } => write!(
+                f,
+                "truncated UTF-8 sequence to write: {bytes} bytes, {expected} expected"
+            ),
+        }
+    }
+}
+
+impl<Byte: std::fmt::Debug> std::error::Error for ErrorType<Byte>
+where
+    ErrorType<Byte>: std::fmt::Display,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ErrorType::StdIO(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Policy for invalid input, see [convert_with]
+///
+/// I/O errors always abort the conversion.
+///
+/// [convert_with]: crate::convert_with
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum OnError {
+    /// abort the conversion on the first input error
+    #[default]
+    Fail,
+    /// skip the invalid input
+    Skip,
+    /// write the provided byte in place of the invalid input
+    Replace(u8),
+}
+
+/// Location of a character in the input stream
+///
+/// Line and column are only meaningful for text formats.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Location {
+    /// number of bytes preceding the character
+    pub offset: usize,
+    /// line number, starting from 1
+    pub line: usize,
+    /// column number, starting from 1
+    pub column: usize,
+}
+
+impl Default for Location {
+    /// First character of the input stream
+    fn default() -> Self {
+        Location {
+            offset: 0,
+            line: 1,
+            column: 1,
+        }
+    }
+}
+
+impl std::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}, column {} (offset {})",
+            self.line, self.column, self.offset
+        )
+    }
+}
+
 /// The error type returned by [convert].
 ///
 /// [convert]: crate::convert
@@ -44,10 +185,86 @@ pub type OutError = ErrorType<u8>;
 pub enum Error {
     /// Input error
     ///
-    /// Error originated in reading or parsing the input
-    In(InError),
+    /// Error originated in reading or parsing the input, at the provided [Location] (that is
+    /// the location of the last character read), if known
+    In(InError, Option<Location>),
     /// Output error
     ///
-    /// Error originated in parsing or writing the output
-    Out(OutError),
+    /// Error originated in parsing or writing the output, while writing the byte at the provided
+    /// offset of the converted byte stream (or, on [finish], after the last one)
+    ///
+    /// [finish]: crate::byte_writer::ByteWriter::finish
+    Out(OutError, usize),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::In(
+                e @ (ErrorType::InvalidLine(_)
+                | ErrorType::InvalidChecksum(_)
+                | ErrorType::InvalidAddress(_)),
+                _,
+            ) => write!(f, "{e}"),
+            Error::In(e, Some(location)) => write!(f, "{e} at {location}"),
+            Error::In(e, None) => write!(f, "{e}"),
+            Error::Out(e, offset) => write!(f, "{e} at byte {offset}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::In(e, _) => Some(e),
+            Error::Out(e, _) => Some(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error as _;
+
+    #[test]
+    fn display() {
+        let location = Location {
+            offset: 345,
+            line: 12,
+            column: 7,
+        };
+        assert_eq!(
+            "invalid input character 'g' at line 12, column 7 (offset 345)",
+            Error::In(InError::InvalidByte('g'), Some(location)).to_string()
+        );
+        assert_eq!(
+            "invalid input character 'g'",
+            Error::In(InError::InvalidByte('g'), None).to_string()
+        );
+        assert_eq!(
+            "byte value 0xff can not be written in the output format at byte 3",
+            Error::Out(OutError::InvalidByte(0xff), 3).to_string()
+        );
+        assert_eq!(
+            "truncated UTF-8 sequence to write: 2 bytes, 3 expected at byte 4",
+            Error::Out(
+                OutError::Truncated {
+                    bytes: 2,
+                    expected: 3
+                },
+                4
+            )
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn source() {
+        let io = IOError::other("broken");
+        let e = Error::Out(OutError::StdIO(io), 0);
+        let source = e.source().unwrap();
+        assert_eq!("broken", source.source().unwrap().to_string());
+        assert!(InError::InvalidLine(1).source().is_none());
+    }
 }
@@ -0,0 +1,307 @@
+use crate::byte_reader::ByteReader;
+use crate::error::{InError, Location};
+use crate::hexadecimal;
+use crate::util;
+use std::io::Read;
+
+/// An iterator over Result<u8,[InError]>
+///
+/// Reads bytes from the input stream as the content of a C, Rust or Python string literal: any
+/// character other than '\\' is read as is, while backslash escapes are interpreted:
+/// - '\\n', '\\t', '\\r', '\\a', '\\b', '\\f', '\\v', '\\\\', '\\"', '\\'' and '\\?'
+/// - '\\xHH', with exactly 2 hexadecimal digits
+/// - '\\NNN', with 1 to 3 octal digits
+/// - '\\u{H...}', with 1 to 6 hexadecimal digits, coding a Unicode character written in UTF-8
+/// - a backslash followed by a newline, which are both skipped
+///
+/// Input may be enclosed in single or double quotes, optionally prefixed by 'b' (g.e. Python
+/// 'b"\\x00"'): in this case whitespace between quoted strings is skipped and adjacent strings are
+/// concatenated (g.e. '"GET /\\r\\n" "Host: a\\r\\n"').
+///
+/// [InError]: crate::error::InError
+pub struct Reader<R: Read> {
+    in_bytes: util::Input<R>,
+    pending: Vec<u8>,
+    started: bool,
+    quoted: bool,
+    quote: Option<u8>,
+    decoded: [u8; 4],
+    available: usize,
+    consumed: usize,
+}
+
+impl<R: Read> Reader<R> {
+    pub fn new(read: R) -> Self {
+        Reader {
+            in_bytes: util::Input::new(read),
+            pending: vec![],
+            started: false,
+            quoted: false,
+            quote: None,
+            decoded: [0u8; 4],
+            available: 0,
+            consumed: 0,
+        }
+    }
+
+    fn next_char(&mut self) -> Option<<util::Input<R> as Iterator>::Item> {
+        match self.pending.pop() {
+            Some(c) => Some(Ok(c)),
+            None => self.in_bytes.next(),
+        }
+    }
+
+    /// Checks whether input is quoted, skipping the 'b' prefix if any
+    fn start(&mut self) -> Result<(), InError> {
+        self.started = true;
+        match self.next_char() {
+            Some(Ok(c)) if c == b'"' || c == b'\'' => self.pending.push(c),
+            Some(Ok(c)) if c == b'b' || c == b'B' => match self.next_char() {
+                Some(Ok(q)) if q == b'"' || q == b'\'' => self.pending.push(q),
+                Some(Ok(q)) => self.pending.extend([q, c]),
+                Some(Err(e)) => return Err(InError::StdIO(e)),
+                None => self.pending.push(c),
+            },
+            Some(Ok(c)) => self.pending.push(c),
+            Some(Err(e)) => return Err(InError::StdIO(e)),
+            None => {}
+        }
+        self.quoted = self
+            .pending
+            .last()
+            .is_some_and(|&c| c == b'"' || c == b'\'');
+        Ok(())
+    }
+
+    /// Reads the character following a '\\' in an escape sequence of the provided length
+    fn escaped(&mut self, read: usize, expected: usize) -> Result<u8, InError> {
+        match self.next_char() {
+            Some(Ok(c)) => Ok(c),
+            Some(Err(e)) => Err(InError::StdIO(e)),
+            None => Err(InError::ShortIO {
+                bytes: read,
+                expected,
+            }),
+        }
+    }
+
+    /// Decodes the escape sequence following a '\\' into self.decoded
+    fn decode_escape(&mut self) -> Result<(), InError> {
+        let c = self.escaped(1, 2)?;
+        let byte = match c {
+            b'n' => b'\n',
+            b't' => b'\t',
+            b'r' => b'\r',
+            b'a' => 0x07,
+            b'b' => 0x08,
+            b'f' => 0x0c,
+            b'v' => 0x0b,
+            b'\\' | b'"' | b'\'' | b'?' => c,
+            b'\n' => {
+                self.available = 0;
+                return Ok(());
+            }
+            b'0'..=b'7' => {
+                let mut value = (c - b'0') as u32;
+                for _ in 0..2 {
+                    match self.next_char() {
+                        Some(Ok(d @ b'0'..=b'7')) => value = value * 8 + (d - b'0') as u32,
+                        Some(Ok(d)) => {
+                            self.pending.push(d);
+                            break;
+                        }
+                        Some(Err(e)) => return Err(InError::StdIO(e)),
+                        None => break,
+                    }
+                }
+                if value > u8::MAX as u32 {
+                    return Err(InError::InvalidByte(c as char));
+                }
+                value as u8
+            }
+            b'x' => {
+                let mut value = 0u8;
+                for read in 2..4 {
+                    let d = self.escaped(read, 4)? as char;
+                    match hexadecimal::valid(d) {
+                        Some(digit) => value = (value << 4) | digit,
+                        None => return Err(InError::InvalidByte(d)),
+                    }
+                }
+                value
+            }
+            b'u' => {
+                let c = self.escaped(2, 5)? as char;
+                if c != '{' {
+                    return Err(InError::InvalidByte(c));
+                }
+                let mut value = 0u32;
+                let mut digits = 0usize;
+                loop {
+                    // '\u{', digits read so far, at least one digit, then '}'
+                    let read = 3 + digits;
+                    let missing = if digits == 0 { 2 } else { 1 };
+                    let d = self.escaped(read, read + missing)? as char;
+                    match (d, hexadecimal::valid(d)) {
+                        ('}', _) if digits > 0 => break,
+                        (_, Some(digit)) if digits < 6 => value = (value << 4) | digit as u32,
+                        _ => return Err(InError::InvalidByte(d)),
+                    }
+                    digits += 1;
+                }
+                let c = char::from_u32(value).ok_or(InError::InvalidByte('}'))?;
+                self.available = c.encode_utf8(&mut self.decoded).len();
+                return Ok(());
+            }
+            _ => return Err(InError::InvalidByte(c as char)),
+        };
+        self.decoded[0] = byte;
+        self.available = 1;
+        Ok(())
+    }
+
+    /// Decodes next character or escape sequence into self.decoded
+    fn decode(&mut self) -> Option<Result<(), InError>> {
+        self.consumed = 0;
+        self.available = 0;
+        while self.available == 0 {
+            let c = match self.next_char() {
+                Some(Ok(c)) => c,
+                Some(Err(e)) => return Some(Err(InError::StdIO(e))),
+                None if self.quote.take().is_some() => {
+                    return Some(Err(InError::ShortIO {
+                        bytes: 0,
+                        expected: 1,
+                    }))
+                }
+                None => return None,
+            };
+            match self.quote {
+                None if self.quoted => match c {
+                    b'"' | b'\'' => self.quote = Some(c),
+                    b'b' | b'B' => match self.next_char() {
+                        Some(Ok(q @ (b'"' | b'\''))) => self.quote = Some(q),
+                        _ => return Some(Err(InError::InvalidByte(c as char))),
+                    },
+                    _ if c.is_ascii_whitespace() => {}
+                    _ => return Some(Err(InError::InvalidByte(c as char))),
+                },
+                Some(q) if c == q => self.quote = None,
+                _ if c == b'\\' => {
+                    if let Err(e) = self.decode_escape() {
+                        return Some(Err(e));
+                    }
+                }
+                _ => {
+                    self.decoded[0] = c;
+                    self.available = 1;
+                }
+            }
+        }
+        Some(Ok(()))
+    }
+}
+
+impl<R: Read> Iterator for Reader<R> {
+    type Item = Result<u8, InError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.started {
+            if let Err(e) = self.start() {
+                return Some(Err(e));
+            }
+        }
+        if self.consumed == self.available {
+            if let Err(e) = self.decode()? {
+                return Some(Err(e));
+            }
+        }
+        self.consumed += 1;
+        Some(Ok(self.decoded[self.consumed - 1]))
+    }
+}
+
+impl<R: Read> ByteReader for Reader<R> {
+    fn location(&self) -> Location {
+        self.in_bytes.location()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(input: &str) -> Vec<u8> {
+        Reader::new(input.as_bytes()).map(|b| b.unwrap()).collect()
+    }
+
+    #[test]
+    fn read() {
+        assert_eq!(
+            b"GET /\r\n\x00\xff".as_slice(),
+            decode(r#"GET /\r\n\x00\xFF"#)
+        );
+        assert_eq!(
+            b"\t\\\"'?\x07\x08\x0c\x0b".as_slice(),
+            decode(r#"\t\\\"\'\?\a\b\f\v"#)
+        );
+        assert_eq!(b"\0\x01A8\xff".as_slice(), decode(r#"\0\1\1018\377"#));
+        assert_eq!("é€😀".as_bytes(), decode(r#"\u{e9}\u{20AC}\u{1f600}"#));
+        assert_eq!(b"ab".as_slice(), decode("a\\\nb"));
+        assert_eq!(b"bad".as_slice(), decode("bad"));
+        assert_eq!(b"\"".as_slice(), decode("\\\""));
+    }
+
+    #[test]
+    fn read_quoted() {
+        assert_eq!(b"a'b\n".as_slice(), decode(r#""a'b\n""#));
+        assert_eq!(b"a\"b".as_slice(), decode(r#"'a"b'"#));
+        assert_eq!(b"\x00\xff".as_slice(), decode("b'\\x00\\xff'\n"));
+        assert_eq!(
+            b"GET /\r\nHost: a\r\n".as_slice(),
+            decode("\"GET /\\r\\n\"\n  \"Host: a\\r\\n\"")
+        );
+        assert_eq!(b"".as_slice(), decode("\"\""));
+    }
+
+    #[test]
+    fn read_invalid() {
+        let mut reader = Reader::new(r#"ab\q"#.as_bytes());
+        reader.next().unwrap().unwrap();
+        reader.next().unwrap().unwrap();
+        assert!(matches!(
+            reader.next(),
+            Some(Err(InError::InvalidByte('q')))
+        ));
+        assert_eq!(3, reader.location().offset);
+        for (input, invalid) in [
+            (r#"\xg0"#, 'g'),
+            (r#"\400"#, '4'),
+            (r#"\u{110000}"#, '}'),
+            (r#"\u{1234567}"#, '7'),
+            (r#"\u{}"#, '}'),
+            (r#"\u20"#, '2'),
+            (r#""a" b"#, 'b'),
+        ] {
+            let error = Reader::new(input.as_bytes()).find_map(|b| b.err());
+            assert!(
+                matches!(error, Some(InError::InvalidByte(c)) if c == invalid),
+                "{input}"
+            );
+        }
+        for (input, bytes, expected) in [
+            (r#"\x4"#, 3, 4),
+            ("\\", 1, 2),
+            ("\"ab", 0, 1),
+            (r#"\u"#, 2, 5),
+            (r#"\u{"#, 3, 5),
+            (r#"\u{4"#, 4, 5),
+            (r#"\u{41"#, 5, 6),
+        ] {
+            let error = Reader::new(input.as_bytes()).find_map(|b| b.err());
+            assert!(
+                matches!(error, Some(InError::ShortIO { bytes: b, expected: e }) if b == bytes && e == expected),
+                "{input}"
+            );
+        }
+    }
+}
@@ -0,0 +1,443 @@
+use crate::byte_reader::ByteReader;
+use crate::byte_writer::ByteWriter;
+use crate::error::{InError, Location, OutError};
+use crate::integer::{Endianness, Integer};
+use crate::text::{Format, Formatter, Tokens};
+use std::io::{Read, Write};
+
+/// IEEE 754 binary floating point type, g.e. 32 bits little endian
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct Float {
+    /// unsigned integer type of the same width, coding the bits
+    bits: Integer,
+}
+
+impl Float {
+    /// Builds a floating point type with the provided number of bits, if 16 (half precision), 32
+    /// (single precision) or 64 (double precision)
+    pub fn new(bits: usize, endianness: Endianness) -> Option<Self> {
+        match bits {
+            16 | 32 | 64 => Some(Float {
+                bits: Integer::new(bits, false, endianness)?,
+            }),
+            _ => None,
+        }
+    }
+
+    pub fn bits(self) -> usize {
+        self.bits.bits()
+    }
+
+    pub fn endianness(self) -> Endianness {
+        self.bits.endianness()
+    }
+
+    /// Bits of the canonical quiet NaN
+    fn nan(self) -> u64 {
+        match self.bits() {
+            16 => 0x7e00,
+            32 => f32::NAN.to_bits() as u64,
+            _ => f64::NAN.to_bits(),
+        }
+    }
+
+    /// Bits of positive infinity
+    fn infinity(self) -> u64 {
+        match self.bits() {
+            16 => 0x7c00,
+            32 => f32::INFINITY.to_bits() as u64,
+            _ => f64::INFINITY.to_bits(),
+        }
+    }
+
+    /// Bits of the provided value, without the sign
+    fn magnitude(self, bits: u64) -> u64 {
+        bits & (u64::MAX >> (65 - self.bits()))
+    }
+
+    fn is_nan(self, bits: u64) -> bool {
+        self.magnitude(bits) > self.infinity()
+    }
+
+    /// Shortest decimal string that reads back as the value coded by the provided bits
+    fn to_string(self, bits: u64) -> String {
+        if self.is_nan(bits) && bits != self.nan() {
+            return format!("NaN:0x{bits:0width$x}", width = self.bits() / 4);
+        }
+        match self.bits() {
+            16 => {
+                let value = f16_to_f32(bits as u16);
+                if !value.is_finite() {
+                    return format!("{value:?}");
+                }
+                // increasing number of significant digits, until the value reads back
+                let digits = (0..)
+                    .map(|precision| format!("{value:.precision$e}"))
+                    .find(|digits| self.parse(digits) == Some(bits))
+                    .unwrap();
+                format!("{:?}", digits.parse::<f64>().unwrap())
+            }
+            32 => format!("{:?}", f32::from_bits(bits as u32)),
+            _ => format!("{:?}", f64::from_bits(bits)),
+        }
+    }
+
+    /// Bits coding the value of the provided decimal string, rounded to the nearest
+    /// (half precision values through double precision, see [Reader])
+    fn parse(self, token: &str) -> Option<u64> {
+        Some(match self.bits() {
+            16 => f64_to_f16(token.parse().ok()?) as u64,
+            32 => token.parse::<f32>().ok()?.to_bits() as u64,
+            _ => token.parse::<f64>().ok()?.to_bits(),
+        })
+    }
+}
+
+/// Converts half precision bits to single precision (exactly)
+fn f16_to_f32(half: u16) -> f32 {
+    let sign = ((half & 0x8000) as u32) << 16;
+    let exponent = ((half >> 10) & 0x1f) as u32;
+    let mantissa = (half & 0x3ff) as u32;
+    match exponent {
+        0 => {
+            let magnitude = mantissa as f32 / (1 << 24) as f32;
+            f32::from_bits(sign | magnitude.to_bits())
+        }
+        0x1f => f32::from_bits(sign | 0x7f80_0000 | (mantissa << 13)),
+        _ => f32::from_bits(sign | ((exponent + 127 - 15) << 23) | (mantissa << 13)),
+    }
+}
+
+/// Converts double precision to half precision bits, rounding to the nearest (ties to even)
+fn f64_to_f16(value: f64) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 48) & 0x8000) as u16;
+    let magnitude = value.abs();
+    if value.is_nan() {
+        return sign | 0x7e00 | ((bits >> 42) & 0x1ff) as u16;
+    }
+    if magnitude >= 65520.0 {
+        return sign | 0x7c00;
+    }
+    if magnitude < 1.0 / (1 << 14) as f64 {
+        // subnormal, possibly rounded up to the smallest normal
+        return sign | (magnitude * (1 << 24) as f64).round_ties_even() as u16;
+    }
+    let exponent = ((bits >> 52) & 0x7ff) + 15 - 1023;
+    let mantissa = bits & ((1 << 52) - 1);
+    let mut half = (exponent << 10) | (mantissa >> 42);
+    let rest = mantissa & ((1 << 42) - 1);
+    if rest > 1 << 41 || (rest == 1 << 41 && half & 1 == 1) {
+        half += 1;
+    }
+    sign | half as u16
+}
+
+/// An iterator over Result<u8,[InError]>
+///
+/// Reads a list of floating point values of the provided [Float] type from the input stream,
+/// returning the bytes coding each of them. Values are split by whitespace, ',', ';' or brackets
+/// and rounded to the nearest value of the type; besides decimal numbers (g.e. '-1.5', '2.5e-3'),
+/// 'inf', '-inf', 'NaN' and 'NaN:0xBITS' (NaN with the provided bits, as written by [Writer])
+/// are allowed, ignoring case.
+///
+/// Finite values beyond the type range are [OutOfRange]. Half precision values are rounded to
+/// double precision first, so a decimal number within rounding error of the tie between two half
+/// precision values may be rounded to the wrong one.
+///
+/// [InError]: crate::error::InError
+/// [OutOfRange]: crate::error::ErrorType::OutOfRange
+pub struct Reader<R: Read> {
+    tokens: Tokens<R>,
+    float: Float,
+}
+
+impl<R: Read> Reader<R> {
+    pub fn new(read: R, float: Float) -> Self {
+        Reader {
+            tokens: Tokens::new(read),
+            float,
+        }
+    }
+
+    /// Parses the provided token as the bits of a value of the provided type
+    fn parse(float: Float, token: &[u8]) -> Result<u64, InError> {
+        let string = String::from_utf8_lossy(token);
+        let invalid = || {
+            let c = token
+                .iter()
+                .find(|c| !c.is_ascii_alphanumeric() && !b"+-.".contains(c))
+                .or(token.last())
+                .unwrap();
+            InError::InvalidByte(*c as char)
+        };
+        if let Some(hex) = string
+            .get(..6)
+            .filter(|nan| nan.eq_ignore_ascii_case("nan:0x"))
+            .and(string.get(6..))
+        {
+            let bits = u64::from_str_radix(hex, 16).map_err(|_| invalid())?;
+            if bits >> 1 >> (float.bits() - 1) != 0 || !float.is_nan(bits) {
+                return Err(InError::OutOfRange);
+            }
+            return Ok(bits);
+        }
+        let bits = float.parse(&string).ok_or_else(invalid)?;
+        let infinity = string
+            .trim_start_matches(['+', '-'])
+            .starts_with(['i', 'I']);
+        if float.magnitude(bits) == float.infinity() && !infinity {
+            return Err(InError::OutOfRange);
+        }
+        Ok(bits)
+    }
+}
+
+impl<R: Read> Iterator for Reader<R> {
+    type Item = Result<u8, InError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let float = self.float;
+        self.tokens
+            .next_byte(|token| Ok(float.bits.encode(Self::parse(float, token)?)))
+    }
+}
+
+impl<R: Read> ByteReader for Reader<R> {
+    fn location(&self) -> Location {
+        self.tokens.location()
+    }
+}
+
+/// Writes bytes to the output stream as a list of floating point values of the provided [Float]
+/// type
+///
+/// Every value is written as the shortest decimal number that reads back as the same value
+/// (using exponential notation for very large or small values, g.e. '1e-40'); infinities are
+/// written as 'inf' and '-inf', NaN as 'NaN' if it is the canonical quiet NaN, else as
+/// 'NaN:0xBITS' to preserve sign and payload.
+///
+/// Every value is coded by as many bytes as the type width: a trailing partial value is a
+/// [ShortIO] error on [finish]. Values are written according to the provided [Format], where a
+/// single space is used if the separator is empty and the prefix is ignored (values would not read
+/// back).
+///
+/// [ShortIO]: crate::error::ErrorType::ShortIO
+/// [finish]: crate::byte_writer::ByteWriter::finish
+/// [Format]: crate::text::Format
+pub struct Writer<W: Write> {
+    out_bytes: W,
+    float: Float,
+    formatter: Formatter,
+    bytes: Vec<u8>,
+}
+
+impl<W: Write> Writer<W> {
+    pub fn new(out_bytes: W, float: Float) -> Self {
+        Self::with_format(out_bytes, float, Format::default())
+    }
+
+    pub fn with_format(out_bytes: W, float: Float, format: Format) -> Self {
+        let format = Format {
+            prefix: String::new(),
+            ..format.with_default_separator()
+        };
+        Writer {
+            out_bytes,
+            float,
+            formatter: Formatter::new(format),
+            bytes: Vec::with_capacity(float.bits() / 8),
+        }
+    }
+}
+
+impl<W: Write> ByteWriter for Writer<W> {
+    fn write(&mut self, byte: u8) -> Result<(), OutError> {
+        self.bytes.push(byte);
+        if self.bytes.len() < self.float.bits() / 8 {
+            return Ok(());
+        }
+        let bits = self.float.bits.decode(&self.bytes);
+        self.bytes.clear();
+        let string = self.float.to_string(bits);
+        self.formatter.write(&mut self.out_bytes, string.as_bytes())
+    }
+
+    fn finish(&mut self) -> Result<(), OutError> {
+        if !self.bytes.is_empty() {
+            return Err(OutError::ShortIO {
+                bytes: self.bytes.len(),
+                expected: self.float.bits() / 8,
+            });
+        }
+        self.formatter.finish(&mut self.out_bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn float(bits: usize, endianness: Endianness) -> Float {
+        Float::new(bits, endianness).unwrap()
+    }
+
+    fn decode(input: &str, float: Float) -> Vec<Result<u8, InError>> {
+        Reader::new(input.as_bytes(), float).collect()
+    }
+
+    fn encode(input: &[u8], float: Float) -> String {
+        let mut output = vec![];
+        let mut writer = Writer::new(&mut output, float);
+        for b in input {
+            writer.write(*b).unwrap();
+        }
+        writer.finish().unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn half() {
+        for (half, value) in [
+            (0x3c00u16, 1.0f32),
+            (0xc000, -2.0),
+            (0x7bff, 65504.0),
+            (0x0001, 5.9604645e-8),
+            (0x03ff, 1023.0 / (1 << 24) as f32),
+            (0x0400, 6.1035156e-5),
+            (0x3555, 0.33325195),
+            (0x7c00, f32::INFINITY),
+            (0x8000, -0.0),
+        ] {
+            assert_eq!(value.to_bits(), f16_to_f32(half).to_bits());
+            assert_eq!(half, f64_to_f16(value as f64));
+        }
+        assert_eq!(0x3c00, f64_to_f16(1.0 + 1.0 / 4096.0));
+        assert_eq!(0x3c02, f64_to_f16(1.0 + 3.0 / 2048.0));
+        assert_eq!(0x7bff, f64_to_f16(65519.0));
+        assert_eq!(0x7c00, f64_to_f16(65520.0));
+        assert_eq!(0x0000, f64_to_f16(2.0f64.powi(-25)));
+        assert_eq!(0x0001, f64_to_f16(2.0f64.powi(-25) * 1.5));
+        assert!(f16_to_f32(0x7e01).is_nan());
+        assert_eq!(0x7e00, f64_to_f16(f64::NAN));
+    }
+
+    #[test]
+    fn read() {
+        let f32le = float(32, Endianness::Little);
+        let bytes = decode("1.5, -2\n[inf]", f32le);
+        assert!(matches!(
+            bytes[..],
+            [
+                Ok(0),
+                Ok(0),
+                Ok(0xc0),
+                Ok(0x3f),
+                Ok(0),
+                Ok(0),
+                Ok(0),
+                Ok(0xc0),
+                Ok(0),
+                Ok(0),
+                Ok(0x80),
+                Ok(0x7f)
+            ]
+        ));
+        let f16be = float(16, Endianness::Big);
+        let bytes = decode("1 -Infinity nan NaN:0xfe01 6e-8", f16be);
+        assert!(matches!(
+            bytes[..],
+            [
+                Ok(0x3c),
+                Ok(0),
+                Ok(0xfc),
+                Ok(0),
+                Ok(0x7e),
+                Ok(0),
+                Ok(0xfe),
+                Ok(0x01),
+                Ok(0),
+                Ok(1)
+            ]
+        ));
+        let f64be = float(64, Endianness::Big);
+        let bytes = decode("0.1", f64be);
+        assert!(matches!(bytes[..], [Ok(0x3f), Ok(0xb9), .., Ok(0x9a)]));
+    }
+
+    #[test]
+    fn read_invalid() {
+        let f32le = float(32, Endianness::Little);
+        let bytes = decode("1e39 1.5x NaN:0x3f800000 NaN:0x1ff800000 1.0.0", f32le);
+        assert!(matches!(
+            bytes[..],
+            [
+                Err(InError::OutOfRange),
+                Err(InError::InvalidByte('x')),
+                Err(InError::OutOfRange),
+                Err(InError::OutOfRange),
+                Err(InError::InvalidByte('0'))
+            ]
+        ));
+        let f16le = float(16, Endianness::Little);
+        assert!(matches!(
+            decode("65520 65519", f16le)[..],
+            [Err(InError::OutOfRange), Ok(0xff), Ok(0x7b)]
+        ));
+    }
+
+    #[test]
+    fn write() {
+        let f32le = float(32, Endianness::Little);
+        let input = [0, 0, 0xc0, 0x3f, 0, 0, 0x80, 0xff, 1, 0, 0, 0];
+        assert_eq!("1.5 -inf 1e-45", encode(&input, f32le));
+        let f32be = float(32, Endianness::Big);
+        let input = [0x7f, 0xc0, 0, 0, 0xff, 0xc0, 0, 0, 0x7f, 0x80, 0, 1];
+        assert_eq!("NaN NaN:0xffc00000 NaN:0x7f800001", encode(&input, f32be));
+        let f16le = float(16, Endianness::Little);
+        assert_eq!("0.3333 -0.0", encode(&[0x55, 0x35, 0, 0x80], f16le));
+        let input = [0x01, 0, 0xff, 0x7b, 0x66, 0x2e];
+        assert_eq!("6e-8 65500.0 0.1", encode(&input, f16le));
+        let f64le = float(64, Endianness::Little);
+        assert_eq!("0.1", encode(&0.1f64.to_le_bytes(), f64le));
+        let mut output = vec![];
+        let format = Format {
+            prefix: "0x".to_string(),
+            ..Default::default()
+        };
+        let mut writer = Writer::with_format(&mut output, f32le, format);
+        for b in [0, 0, 0xc0, 0x3f, 0, 0, 0x80, 0x3f] {
+            writer.write(b).unwrap();
+        }
+        writer.finish().unwrap();
+        assert_eq!(b"1.5 1.0".as_slice(), output);
+        let mut output = vec![];
+        let mut writer = Writer::new(&mut output, f64le);
+        writer.write(0).unwrap();
+        assert!(matches!(
+            writer.finish(),
+            Err(OutError::ShortIO {
+                bytes: 1,
+                expected: 8
+            })
+        ));
+    }
+
+    #[test]
+    fn roundtrip() {
+        for bits in [16, 32, 64] {
+            for endianness in [Endianness::Little, Endianness::Big] {
+                let float = float(bits, endianness);
+                let input: Vec<u8> = (0..=255u8)
+                    .flat_map(|b| [b, 0x80 ^ b, 0xff - b, b.wrapping_mul(37)])
+                    .collect();
+                let output = encode(&input, float);
+                let bytes: Vec<u8> = decode(&output, float)
+                    .into_iter()
+                    .map(|b| b.unwrap())
+                    .collect();
+                assert_eq!(input, bytes);
+            }
+        }
+        assert!(Float::new(8, Endianness::Little).is_none());
+    }
+}
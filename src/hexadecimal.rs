@@ -1,49 +1,54 @@
+use crate::byte_reader::ByteReader;
 use crate::byte_writer::ByteWriter;
-use crate::error::{InError, OutError};
-use crate::util;
-use std::io::{Bytes, Read, Write};
+use crate::error::{InError, Location, OutError};
+use crate::text::{Format, Formatter, Lenient, Scanner};
+use std::io::{Read, Write};
+
+/// Returns the value of hexadecimal digit n, if valid
+pub(crate) fn valid(n: char) -> Option<u8> {
+    if n.is_ascii_digit() {
+        Some(n as u8 - b'0')
+    } else if ('a'..='f').contains(&n) {
+        Some(10u8 + (n as u8 - b'a'))
+    } else if ('A'..='F').contains(&n) {
+        Some(10u8 + (n as u8 - b'A'))
+    } else {
+        None
+    }
+}
+
+/// Returns the bytes coded by a string of hexadecimal digit pairs, if valid
+pub(crate) fn decode(hex: &[u8]) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    hex.chunks(2)
+        .map(|pair| Some((valid(pair[0] as char)? << 4) | valid(pair[1] as char)?))
+        .collect()
+}
 
 /// An iterator over Result<u8,[InError]>
 ///
 /// Reads bytes from the input stream in hexadecimal base format, that is a multiple of 2 characters in the ranges ('0','9'), ('a','f') or ('A', 'F') are allowed (and any number of whitespace characters that will be skipped)
 ///
+/// In [Lenient] mode, separators and prefixes are allowed too.
+///
 /// [InError]: crate::error::InError
+/// [Lenient]: crate::text::Lenient
 pub struct Reader<R: Read> {
-    in_bytes: Bytes<R>,
+    scanner: Scanner<R>,
 }
 
 impl<R: Read> Reader<R> {
     pub fn new(read: R) -> Self {
         Reader {
-            in_bytes: read.bytes(),
+            scanner: Scanner::new(read, None),
         }
     }
-    fn valid(n: char) -> Option<u8> {
-        if ('0'..='9').contains(&n) {
-            Some(n as u8 - b'0')
-        } else if ('a'..='f').contains(&n) {
-            Some(10u8 + (n as u8 - b'a'))
-        } else if ('A'..='F').contains(&n) {
-            Some(10u8 + (n as u8 - b'A'))
-        } else {
-            None
-        }
-    }
-    fn next_non_whitespace(&mut self) -> Option<<Bytes<R> as Iterator>::Item> {
-        loop {
-            let c = self.in_bytes.next()?;
-            match c {
-                Ok(c) => {
-                    if c.is_ascii_whitespace() {
-                        continue;
-                    } else {
-                        return Some(Ok(c));
-                    }
-                }
-                Err(e) => {
-                    return Some(Err(e));
-                }
-            }
+
+    pub fn lenient(read: R, lenient: Lenient) -> Self {
+        Reader {
+            scanner: Scanner::new(read, Some(lenient)),
         }
     }
 }
@@ -51,46 +56,37 @@ impl<R: Read> Reader<R> {
 impl<R: Read> Iterator for Reader<R> {
     type Item = Result<u8, InError>;
     fn next(&mut self) -> Option<Self::Item> {
-        let msn = self.next_non_whitespace()?;
-        match msn {
-            Ok(msn) => {
-                if let Some(msn) = Self::valid(msn as char) {
-                    if let Some(lsn) = self.next_non_whitespace() {
-                        match lsn {
-                            Ok(lsn) => {
-                                if let Some(lsn) = Self::valid(lsn as char) {
-                                    Some(Ok((msn << 4) | lsn))
-                                } else {
-                                    Some(Err(InError::InvalidByte(lsn as char)))
-                                }
-                            }
-                            Err(e) => Some(Err(InError::StdIO(e))),
-                        }
-                    } else {
-                        Some(Err(InError::ShortIO {
-                            bytes: 1usize,
-                            expected: 2,
-                        }))
-                    }
-                } else {
-                    Some(Err(InError::InvalidByte(msn as char)))
-                }
-            }
-            Err(e) => Some(Err(InError::StdIO(e))),
-        }
+        self.scanner.next_byte(2, 16, valid)
+    }
+}
+
+impl<R: Read> ByteReader for Reader<R> {
+    fn location(&self) -> Location {
+        self.scanner.location()
     }
 }
 
 /// Writes bytes to the output stream in the hexadecimal format
 ///
-/// Produced characters are in the ranges ('0', '9') and ('a', 'f')
+/// Produced characters are in the ranges ('0', '9') and ('a', 'f'), unless otherwise specified
+/// by the provided [Format]
+///
+/// [Format]: crate::text::Format
 pub struct Writer<W: Write> {
     out_bytes: W,
+    formatter: Formatter,
 }
 
 impl<W: Write> Writer<W> {
     pub fn new(out_bytes: W) -> Self {
-        Writer { out_bytes }
+        Self::with_format(out_bytes, Format::default())
+    }
+
+    pub fn with_format(out_bytes: W, format: Format) -> Self {
+        Writer {
+            out_bytes,
+            formatter: Formatter::new(format),
+        }
     }
 }
 
@@ -98,7 +94,11 @@ impl<W: Write> ByteWriter for Writer<W> {
     fn write(&mut self, byte: u8) -> Result<(), OutError> {
         let msn = char::from_digit(((byte & 0xf0) >> 4) as u32, 16).unwrap() as u8;
         let lsn = char::from_digit((byte & 0x0f) as u32, 16).unwrap() as u8;
-        util::write(&mut self.out_bytes, &[msn, lsn], 2)
+        self.formatter.write(&mut self.out_bytes, &[msn, lsn])
+    }
+
+    fn finish(&mut self) -> Result<(), OutError> {
+        self.formatter.finish(&mut self.out_bytes)
     }
 }
 
@@ -116,6 +116,20 @@ mod tests {
         assert!(reader.next().is_none());
     }
 
+    #[test]
+    fn read_lenient() {
+        let input = b"de:ad:BE:EF";
+        let reader = Reader::lenient(input.as_slice(), Lenient::hexadecimal());
+        let output: Vec<u8> = reader.map(|b| b.unwrap()).collect();
+        assert_eq!([0xde, 0xad, 0xbe, 0xef].as_slice(), output);
+        let mut reader = Reader::new(input.as_slice());
+        assert_eq!(0xde, reader.next().unwrap().unwrap());
+        assert!(matches!(
+            reader.next(),
+            Some(Err(InError::InvalidByte(':')))
+        ));
+    }
+
     #[test]
     fn write() {
         let input = 0xf4;
@@ -0,0 +1,378 @@
+use crate::byte_reader::ByteReader;
+use crate::byte_writer::ByteWriter;
+use crate::error::{InError, Location, OutError};
+use crate::hexadecimal;
+use crate::util;
+use std::collections::VecDeque;
+use std::io::{Read, Write};
+
+/// Hex dump layout
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Style {
+    /// `xxd` layout (g.e. '00000000: 6865 6c6c 6f0a  hello.'); by default 16 bytes per line in
+    /// groups of 2
+    Xxd,
+    /// `hexdump -C` layout (g.e. '00000000  68 65 6c 6c 6f 0a  |hello.|'), followed by a line
+    /// with the total number of bytes; by default 16 bytes per line in groups of 8
+    Canonical,
+}
+
+/// An iterator over Result<u8,[InError]>
+///
+/// Reads bytes from a hex dump in any [Style] (the layout is detected line by line): every line
+/// begins with the hexadecimal offset of its first byte (followed by ':' for xxd), then the
+/// hexadecimal values of the bytes, optionally followed by their ASCII representation (after two
+/// spaces for xxd, enclosed in '|' for hexdump -C). Empty lines are skipped.
+///
+/// Offsets can skip ahead, in which case the gap is filled with zeros or, if the skipped lines
+/// were collapsed by a '*' line, with repetitions of the previous line; offsets can not go back.
+/// A line holding only an offset marks the end of the dump.
+///
+/// [InError]: crate::error::InError
+pub struct Reader<R: Read> {
+    in_bytes: util::Input<R>,
+    line_number: usize,
+    offset: usize,
+    previous: Vec<u8>,
+    repeat: bool,
+    ended: bool,
+    gap: usize,
+    fill: Vec<u8>,
+    filled: usize,
+    decoded: VecDeque<u8>,
+}
+
+impl<R: Read> Reader<R> {
+    pub fn new(read: R) -> Self {
+        Reader {
+            in_bytes: util::Input::new(read),
+            line_number: 0,
+            offset: 0,
+            previous: vec![],
+            repeat: false,
+            ended: false,
+            gap: 0,
+            fill: vec![],
+            filled: 0,
+            decoded: VecDeque::new(),
+        }
+    }
+
+    fn next_line(&mut self) -> Option<Result<String, InError>> {
+        let mut line = vec![];
+        loop {
+            match self.in_bytes.next() {
+                None if line.is_empty() => return None,
+                None | Some(Ok(b'\n')) => break,
+                Some(Ok(c)) => line.push(c),
+                Some(Err(e)) => return Some(Err(InError::StdIO(e))),
+            }
+        }
+        self.line_number += 1;
+        match String::from_utf8(line) {
+            Ok(line) => Some(Ok(line)),
+            Err(_) => Some(Err(InError::InvalidLine(self.line_number))),
+        }
+    }
+
+    /// Decodes next line into self.decoded, after setting self.gap to the number of bytes to be
+    /// filled before it
+    fn decode_line(&mut self) -> Option<Result<(), InError>> {
+        let line = match self.next_line()? {
+            Ok(line) => line,
+            Err(e) => return Some(Err(e)),
+        };
+        let invalid = InError::InvalidLine(self.line_number);
+        let line = line.trim();
+        if line.is_empty() {
+            return Some(Ok(()));
+        }
+        if line == "*" {
+            self.repeat = true;
+            return Some(Ok(()));
+        }
+        let offset_len = line
+            .find(|c: char| !c.is_ascii_hexdigit())
+            .unwrap_or(line.len());
+        let offset = match usize::from_str_radix(&line[..offset_len], 16) {
+            Ok(offset) if offset >= self.offset => offset,
+            _ => return Some(Err(invalid)),
+        };
+        let rest = &line[offset_len..];
+        let hex = if let Some(rest) = rest.strip_prefix(':') {
+            rest.trim_start().split("  ").next().unwrap()
+        } else if rest.is_empty() || rest.starts_with(char::is_whitespace) {
+            rest.split('|').next().unwrap()
+        } else {
+            return Some(Err(invalid));
+        };
+        let mut bytes = vec![];
+        let mut msn = None;
+        for c in hex.chars().filter(|c| !c.is_whitespace()) {
+            match (hexadecimal::valid(c), msn) {
+                (Some(lsn), Some(m)) => {
+                    bytes.push((m << 4) | lsn);
+                    msn = None;
+                }
+                (Some(n), None) => msn = Some(n),
+                (None, _) => return Some(Err(invalid)),
+            }
+        }
+        if msn.is_some() {
+            return Some(Err(invalid));
+        }
+        self.gap = offset - self.offset;
+        self.fill = if self.repeat && !self.previous.is_empty() {
+            self.previous.clone()
+        } else {
+            vec![0u8]
+        };
+        self.filled = 0;
+        self.offset = offset;
+        self.repeat = false;
+        if rest.is_empty() {
+            self.ended = true;
+        } else if !bytes.is_empty() {
+            self.offset += bytes.len();
+            self.decoded.extend(&bytes);
+            self.previous = bytes;
+        }
+        Some(Ok(()))
+    }
+}
+
+impl<R: Read> Iterator for Reader<R> {
+    type Item = Result<u8, InError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.gap == 0 && self.decoded.is_empty() && !self.ended {
+            if let Err(e) = self.decode_line()? {
+                return Some(Err(e));
+            }
+        }
+        if self.gap > 0 {
+            self.gap -= 1;
+            self.filled += 1;
+            return Some(Ok(self.fill[(self.filled - 1) % self.fill.len()]));
+        }
+        Some(Ok(self.decoded.pop_front()?))
+    }
+}
+
+impl<R: Read> ByteReader for Reader<R> {
+    fn location(&self) -> Location {
+        self.in_bytes.location()
+    }
+}
+
+/// Writes bytes to the output stream as an annotated hex dump
+///
+/// Every line begins with the offset of its first byte, followed by the hexadecimal values of the
+/// bytes (lowercase, grouped) and by their ASCII representation, where non printable characters
+/// are shown as '.'. The last partial line is only written on [finish].
+///
+/// [finish]: crate::byte_writer::ByteWriter::finish
+pub struct Writer<W: Write> {
+    out_bytes: W,
+    style: Style,
+    columns: usize,
+    group: usize,
+    line: Vec<u8>,
+    offset: usize,
+}
+
+impl<W: Write> Writer<W> {
+    pub fn new(out_bytes: W, style: Style) -> Self {
+        match style {
+            Style::Xxd => Self::with_layout(out_bytes, style, 16, 2),
+            Style::Canonical => Self::with_layout(out_bytes, style, 16, 8),
+        }
+    }
+
+    /// Builds a writer with the provided number of bytes per line (columns) and bytes per group
+    pub fn with_layout(out_bytes: W, style: Style, columns: usize, group: usize) -> Self {
+        Writer {
+            out_bytes,
+            style,
+            columns: columns.max(1),
+            group: group.max(1),
+            line: vec![],
+            offset: 0,
+        }
+    }
+
+    fn write_line(&mut self) -> Result<(), OutError> {
+        let mut string = match self.style {
+            Style::Xxd => format!("{:08x}: ", self.offset),
+            Style::Canonical => format!("{:08x}  ", self.offset),
+        };
+        for i in 0..self.columns {
+            let hex = match (self.line.get(i), self.style) {
+                (Some(b), Style::Xxd) => format!("{b:02x}"),
+                (Some(b), Style::Canonical) => format!("{b:02x} "),
+                (None, Style::Xxd) => "  ".to_string(),
+                (None, Style::Canonical) => "   ".to_string(),
+            };
+            string.push_str(&hex);
+            let group_end = (i + 1) % self.group == 0;
+            if group_end || (self.style == Style::Xxd && i == self.columns - 1) {
+                string.push(' ');
+            }
+        }
+        let ascii: String = self
+            .line
+            .iter()
+            .map(|&b| {
+                if b.is_ascii_graphic() || b == b' ' {
+                    b as char
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+        match self.style {
+            Style::Xxd => string.push_str(&format!(" {ascii}\n")),
+            Style::Canonical => string.push_str(&format!("|{ascii}|\n")),
+        }
+        self.offset += self.line.len();
+        self.line.clear();
+        util::write(&mut self.out_bytes, string.as_bytes(), string.len())
+    }
+}
+
+impl<W: Write> ByteWriter for Writer<W> {
+    fn write(&mut self, byte: u8) -> Result<(), OutError> {
+        self.line.push(byte);
+        if self.line.len() == self.columns {
+            self.write_line()
+        } else {
+            Ok(())
+        }
+    }
+
+    fn finish(&mut self) -> Result<(), OutError> {
+        if !self.line.is_empty() {
+            self.write_line()?;
+        }
+        if self.style == Style::Canonical && self.offset > 0 {
+            let string = format!("{:08x}\n", self.offset);
+            util::write(&mut self.out_bytes, string.as_bytes(), string.len())?;
+        }
+        util::flush(&mut self.out_bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &[u8; 17] = b"Hello, World!\n\x00\xffA";
+
+    fn dump(mut writer: Writer<&mut Vec<u8>>) {
+        for b in INPUT {
+            writer.write(*b).unwrap();
+        }
+        writer.finish().unwrap();
+    }
+
+    #[test]
+    fn read_xxd() {
+        let input = "00000000: 4865 6c6c 6f2c 2057 6f72 6c64 210a 00ff  Hello, World!...\n\
+                     00000010: 41                                       A\n";
+        let reader = Reader::new(input.as_bytes());
+        let output: Vec<u8> = reader.map(|b| b.unwrap()).collect();
+        assert_eq!(INPUT.as_slice(), output);
+    }
+
+    #[test]
+    fn read_canonical() {
+        let input =
+            "00000000  48 65 6c 6c 6f 2c 20 57  6f 72 6c 64 21 0a 00 ff  |Hello, World!...|\r\n\
+                     00000010  41                                                |A|\r\n\
+                     00000011\r\n";
+        let reader = Reader::new(input.as_bytes());
+        let output: Vec<u8> = reader.map(|b| b.unwrap()).collect();
+        assert_eq!(INPUT.as_slice(), output);
+    }
+
+    #[test]
+    fn read_sparse() {
+        let input = "00000002: 4142  AB\n00000006: 43  C\n";
+        let reader = Reader::new(input.as_bytes());
+        let output: Vec<u8> = reader.map(|b| b.unwrap()).collect();
+        assert_eq!(b"\0\0AB\0\0C".as_slice(), output);
+        let input = "00000000  41 42  |AB|\n*\n00000006  43  |C|\n00000008\n";
+        let reader = Reader::new(input.as_bytes());
+        let output: Vec<u8> = reader.map(|b| b.unwrap()).collect();
+        assert_eq!(b"ABABABC\0".as_slice(), output);
+    }
+
+    #[test]
+    fn read_large_gap() {
+        let input = "00000000: 4142  AB\n0fffffffffff: 43  C\n";
+        let mut reader = Reader::new(input.as_bytes());
+        let output: Vec<u8> = reader.by_ref().take(4).map(|b| b.unwrap()).collect();
+        assert_eq!(b"AB\0\0".as_slice(), output);
+        assert_eq!(0x0fff_ffff_fffb, reader.gap);
+    }
+
+    #[test]
+    fn read_invalid() {
+        let input = "00000000: 4142  AB\n00000001: 43  C\n";
+        let mut reader = Reader::new(input.as_bytes());
+        reader.next().unwrap().unwrap();
+        reader.next().unwrap().unwrap();
+        assert!(matches!(reader.next(), Some(Err(InError::InvalidLine(2)))));
+        for input in [
+            "00000000: 414  A\n",
+            "00000000: 4g  A\n",
+            "0000000x: 41  A\n",
+        ] {
+            let mut reader = Reader::new(input.as_bytes());
+            assert!(matches!(reader.next(), Some(Err(InError::InvalidLine(1)))));
+        }
+    }
+
+    #[test]
+    fn xxd() {
+        let mut output = vec![];
+        dump(Writer::new(&mut output, Style::Xxd));
+        assert_eq!(
+            "00000000: 4865 6c6c 6f2c 2057 6f72 6c64 210a 00ff  Hello, World!...\n\
+             00000010: 41                                       A\n",
+            String::from_utf8(output).unwrap()
+        );
+    }
+
+    #[test]
+    fn xxd_layout() {
+        let mut output = vec![];
+        dump(Writer::with_layout(&mut output, Style::Xxd, 5, 2));
+        assert_eq!(
+            "00000000: 4865 6c6c 6f  Hello\n\
+             00000005: 2c20 576f 72  , Wor\n\
+             0000000a: 6c64 210a 00  ld!..\n\
+             0000000f: ff41          .A\n",
+            String::from_utf8(output).unwrap()
+        );
+    }
+
+    #[test]
+    fn canonical() {
+        let mut output = vec![];
+        dump(Writer::new(&mut output, Style::Canonical));
+        assert_eq!(
+            "00000000  48 65 6c 6c 6f 2c 20 57  6f 72 6c 64 21 0a 00 ff  |Hello, World!...|\n\
+             00000010  41                                                |A|\n\
+             00000011\n",
+            String::from_utf8(output).unwrap()
+        );
+    }
+
+    #[test]
+    fn empty() {
+        let mut output = vec![];
+        let mut writer = Writer::new(&mut output, Style::Canonical);
+        writer.finish().unwrap();
+        assert!(output.is_empty());
+    }
+}
@@ -0,0 +1,300 @@
+use crate::byte_reader::ByteReader;
+use crate::byte_writer::ByteWriter;
+use crate::error::{InError, Location, OutError};
+use crate::hexadecimal;
+use crate::image::{self, Image};
+use crate::util;
+use std::io::{Read, Write};
+
+const DATA: u8 = 0x00;
+const END_OF_FILE: u8 = 0x01;
+const EXTENDED_SEGMENT_ADDRESS: u8 = 0x02;
+const START_SEGMENT_ADDRESS: u8 = 0x03;
+const EXTENDED_LINEAR_ADDRESS: u8 = 0x04;
+const START_LINEAR_ADDRESS: u8 = 0x05;
+
+/// An iterator over Result<u8,[InError]>
+///
+/// Reads the memory image coded by an Intel HEX file: every line is a record (':', byte count,
+/// 16 bits address, record type, data and checksum, in hexadecimal) whose checksum is validated.
+/// Data records are placed at their address, extended by the last extended segment or extended
+/// linear address record; start address records are ignored and the end of file record ends the
+/// input. Empty lines are skipped.
+///
+/// The image begins at the lowest address; gaps between data records are filled with the provided
+/// fill byte (0xff by default, as erased flash memory), while overlapping records are invalid.
+/// Since records can come in any order, the whole input is read before the first byte is
+/// returned.
+///
+/// [InError]: crate::error::InError
+pub struct Reader<R: Read> {
+    in_bytes: util::Input<R>,
+    fill: u8,
+    line_number: usize,
+    base: u64,
+    image: Option<Image>,
+    bytes: Option<image::Bytes>,
+}
+
+impl<R: Read> Reader<R> {
+    pub fn new(read: R) -> Self {
+        Self::with_fill(read, 0xff)
+    }
+
+    /// Builds a reader filling gaps between data records with the provided byte
+    pub fn with_fill(read: R, fill: u8) -> Self {
+        Reader {
+            in_bytes: util::Input::new(read),
+            fill,
+            line_number: 0,
+            base: 0,
+            image: Some(Image::new()),
+            bytes: None,
+        }
+    }
+
+    /// Reads next record into self.image; returns false at the end of file
+    fn read_record(&mut self) -> Result<bool, InError> {
+        let line = match self.in_bytes.line() {
+            Some(line) => line.map_err(InError::StdIO)?,
+            None => return Ok(false),
+        };
+        self.line_number += 1;
+        let invalid = InError::InvalidLine(self.line_number);
+        let line = line.trim_ascii();
+        if line.is_empty() {
+            return Ok(true);
+        }
+        let Some(hex) = line.strip_prefix(b":") else {
+            return Err(invalid);
+        };
+        let Some(record) = hexadecimal::decode(hex) else {
+            return Err(invalid);
+        };
+        if record.len() < 5 || record.len() != record[0] as usize + 5 {
+            return Err(invalid);
+        }
+        if record.iter().fold(0u8, |sum, &b| sum.wrapping_add(b)) != 0 {
+            return Err(InError::InvalidChecksum(self.line_number));
+        }
+        let address = u16::from_be_bytes([record[1], record[2]]) as u64;
+        let data = &record[4..record.len() - 1];
+        match (record[3], data.len()) {
+            (DATA, _) => {
+                let image = self.image.as_mut().unwrap();
+                if !image.insert(self.base + address, data) {
+                    return Err(InError::InvalidAddress(self.line_number));
+                }
+            }
+            (END_OF_FILE, 0) => return Ok(false),
+            (EXTENDED_SEGMENT_ADDRESS, 2) => {
+                self.base = (u16::from_be_bytes([data[0], data[1]]) as u64) << 4;
+            }
+            (EXTENDED_LINEAR_ADDRESS, 2) => {
+                self.base = (u16::from_be_bytes([data[0], data[1]]) as u64) << 16;
+            }
+            (START_SEGMENT_ADDRESS | START_LINEAR_ADDRESS, 4) => {}
+            _ => return Err(invalid),
+        }
+        Ok(true)
+    }
+}
+
+impl<R: Read> Iterator for Reader<R> {
+    type Item = Result<u8, InError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.image.is_some() {
+            match self.read_record() {
+                Ok(true) => {}
+                Ok(false) => {
+                    let image = self.image.take().unwrap();
+                    self.bytes = Some(image.into_bytes(self.fill));
+                }
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        Some(Ok(self.bytes.as_mut()?.next()?))
+    }
+}
+
+impl<R: Read> ByteReader for Reader<R> {
+    fn location(&self) -> Location {
+        self.in_bytes.location()
+    }
+}
+
+/// Writes bytes to the output stream as an Intel HEX file
+///
+/// Bytes are written in data records of the provided length (16 by default) starting from the
+/// provided base address (0 by default); extended linear address records are written whenever
+/// the upper 16 bits of the address change. The last partial record and the end of file record
+/// are only written on [finish].
+///
+/// [finish]: crate::byte_writer::ByteWriter::finish
+pub struct Writer<W: Write> {
+    out_bytes: W,
+    record_length: usize,
+    address: u64,
+    base: u64,
+    line_number: usize,
+    record: Vec<u8>,
+}
+
+impl<W: Write> Writer<W> {
+    pub fn new(out_bytes: W) -> Self {
+        Self::with_layout(out_bytes, 16, 0)
+    }
+
+    /// Builds a writer with the provided number of bytes per data record (up to 255) and address
+    /// of the first byte
+    pub fn with_layout(out_bytes: W, record_length: usize, address: u32) -> Self {
+        Writer {
+            out_bytes,
+            record_length: record_length.clamp(1, 255),
+            address: address as u64,
+            base: 0,
+            line_number: 0,
+            record: vec![],
+        }
+    }
+
+    fn write_record(&mut self, record_type: u8, address: u16, data: &[u8]) -> Result<(), OutError> {
+        let mut record = vec![data.len() as u8];
+        record.extend(address.to_be_bytes());
+        record.push(record_type);
+        record.extend(data);
+        let sum = record.iter().fold(0u8, |sum, &b| sum.wrapping_add(b));
+        record.push(sum.wrapping_neg());
+        let mut string = String::with_capacity(2 * record.len() + 2);
+        string.push(':');
+        for b in record {
+            string.push_str(&format!("{b:02X}"));
+        }
+        string.push('\n');
+        self.line_number += 1;
+        util::write(&mut self.out_bytes, string.as_bytes(), string.len())
+    }
+
+    fn write_data(&mut self) -> Result<(), OutError> {
+        if self.record.is_empty() {
+            return Ok(());
+        }
+        if self.address + self.record.len() as u64 > 1 << 32 {
+            return Err(OutError::InvalidAddress(self.line_number + 1));
+        }
+        let base = self.address & !0xffff;
+        if base != self.base {
+            self.base = base;
+            let upper = ((base >> 16) as u16).to_be_bytes();
+            self.write_record(EXTENDED_LINEAR_ADDRESS, 0, &upper)?;
+        }
+        let record = std::mem::take(&mut self.record);
+        self.write_record(DATA, self.address as u16, &record)?;
+        self.address += record.len() as u64;
+        Ok(())
+    }
+}
+
+impl<W: Write> ByteWriter for Writer<W> {
+    fn write(&mut self, byte: u8) -> Result<(), OutError> {
+        self.record.push(byte);
+        // records do not cross 64 KiB boundaries
+        let boundary = (self.address + self.record.len() as u64).trailing_zeros() >= 16;
+        if self.record.len() == self.record_length || boundary {
+            self.write_data()
+        } else {
+            Ok(())
+        }
+    }
+
+    fn finish(&mut self) -> Result<(), OutError> {
+        self.write_data()?;
+        self.write_record(END_OF_FILE, 0, &[])?;
+        util::flush(&mut self.out_bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(input: &[u8], record_length: usize, address: u32) -> String {
+        let mut output = vec![];
+        let mut writer = Writer::with_layout(&mut output, record_length, address);
+        for b in input {
+            writer.write(*b).unwrap();
+        }
+        writer.finish().unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    fn decode(input: &str) -> Vec<u8> {
+        Reader::new(input.as_bytes()).map(|b| b.unwrap()).collect()
+    }
+
+    #[test]
+    fn read() {
+        let input = ":10010000214601360121470136007EFE09D2190140\n\
+                     :100110002146017E17C20001FF5F16002148011928\n\
+                     :00000001FF\n";
+        let output = decode(input);
+        assert_eq!(32, output.len());
+        assert_eq!([0x21, 0x46, 0x01, 0x36].as_slice(), &output[..4]);
+        assert_eq!([0x01, 0x19].as_slice(), &output[30..]);
+    }
+
+    #[test]
+    fn read_address() {
+        let input = ":020000040001F9\r\n\
+                     :02000200AABB97\r\n\
+                     \r\n\
+                     :01000000CC33\r\n\
+                     :0400000500000000F7\r\n\
+                     :00000001FF\r\n\
+                     :01000000DD22\r\n";
+        let reader = Reader::with_fill(input.as_bytes(), 0);
+        let output: Vec<u8> = reader.map(|b| b.unwrap()).collect();
+        assert_eq!([0xcc, 0, 0xaa, 0xbb].as_slice(), output);
+        let input = ":020000021000EC\n:02000000AABB99\n";
+        assert_eq!([0xaa, 0xbb].as_slice(), decode(input));
+    }
+
+    #[test]
+    fn read_invalid() {
+        let input = ":0100000041BE\n:0100000041BF\n:0100000042BD\n:01000000xxBD\n0100000042BD\n";
+        let mut reader = Reader::new(input.as_bytes());
+        assert!(matches!(
+            reader.next(),
+            Some(Err(InError::InvalidChecksum(2)))
+        ));
+        assert!(matches!(
+            reader.next(),
+            Some(Err(InError::InvalidAddress(3)))
+        ));
+        assert!(matches!(reader.next(), Some(Err(InError::InvalidLine(4)))));
+        assert!(matches!(reader.next(), Some(Err(InError::InvalidLine(5)))));
+        assert_eq!(0x41, reader.next().unwrap().unwrap());
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn write() {
+        assert_eq!(":0300000041424337\n:00000001FF\n", encode(b"ABC", 16, 0));
+        assert_eq!(
+            ":020000040001F9\n:02FFFE0041427E\n:020000040002F8\n:0100000043BC\n:00000001FF\n",
+            encode(b"ABC", 16, 0x1fffe)
+        );
+        assert_eq!(":00000001FF\n", encode(b"", 16, 0));
+        let mut output = vec![];
+        let mut writer = Writer::with_layout(&mut output, 16, u32::MAX);
+        writer.write(0).unwrap();
+        writer.write(0).unwrap();
+        assert!(matches!(writer.finish(), Err(OutError::InvalidAddress(3))));
+    }
+
+    #[test]
+    fn roundtrip() {
+        let input: Vec<u8> = (0..=255).collect();
+        assert_eq!(input, decode(&encode(&input, 32, 0x1234)));
+    }
+}
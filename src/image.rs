@@ -0,0 +1,99 @@
+use std::collections::{btree_map, BTreeMap};
+
+/// Memory image made of data records at arbitrary addresses (f.e. read from an Intel HEX file)
+pub(crate) struct Image {
+    segments: BTreeMap<u64, Vec<u8>>,
+}
+
+impl Image {
+    pub(crate) fn new() -> Self {
+        Image {
+            segments: BTreeMap::new(),
+        }
+    }
+
+    /// Adds data at the provided address; returns false if it overlaps data already added
+    pub(crate) fn insert(&mut self, address: u64, data: &[u8]) -> bool {
+        let end = address + data.len() as u64;
+        if let Some((&next, _)) = self.segments.range(address..).next() {
+            if next < end {
+                return false;
+            }
+        }
+        if let Some((&start, previous)) = self.segments.range_mut(..address).next_back() {
+            let previous_end = start + previous.len() as u64;
+            if previous_end > address {
+                return false;
+            }
+            if previous_end == address {
+                previous.extend(data);
+                return true;
+            }
+        }
+        if !data.is_empty() {
+            self.segments.insert(address, data.to_vec());
+        }
+        true
+    }
+
+    /// Bytes of the image, starting from the lowest address, with gaps filled with fill
+    pub(crate) fn into_bytes(self, fill: u8) -> Bytes {
+        Bytes {
+            segments: self.segments.into_iter(),
+            address: None,
+            gap: 0,
+            data: vec![].into_iter(),
+            fill,
+        }
+    }
+}
+
+/// An iterator over the bytes of an [Image]
+pub(crate) struct Bytes {
+    segments: btree_map::IntoIter<u64, Vec<u8>>,
+    address: Option<u64>,
+    gap: u64,
+    data: std::vec::IntoIter<u8>,
+    fill: u8,
+}
+
+impl Iterator for Bytes {
+    type Item = u8;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.gap > 0 {
+                self.gap -= 1;
+                return Some(self.fill);
+            }
+            if let Some(byte) = self.data.next() {
+                return Some(byte);
+            }
+            let (start, data) = self.segments.next()?;
+            self.gap = self.address.map_or(0, |address| start - address);
+            self.address = Some(start + data.len() as u64);
+            self.data = data.into_iter();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flatten() {
+        let mut image = Image::new();
+        assert!(image.insert(0x10, &[3, 4]));
+        assert!(image.insert(0x08, &[1]));
+        assert!(image.insert(0x09, &[2]));
+        assert!(image.insert(0x14, &[]));
+        assert!(!image.insert(0x11, &[5]));
+        assert!(!image.insert(0x07, &[5, 5]));
+        let bytes: Vec<u8> = image.into_bytes(0xff).collect();
+        assert_eq!(
+            [1, 2, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 3, 4].as_slice(),
+            bytes
+        );
+        assert_eq!(0, Image::new().into_bytes(0).count());
+    }
+}
@@ -0,0 +1,376 @@
+use crate::byte_reader::ByteReader;
+use crate::byte_writer::ByteWriter;
+use crate::error::{InError, Location, OutError};
+use crate::text::{Format, Formatter, Tokens};
+use std::io::{Read, Write};
+
+/// Byte order of numbers wider than a byte
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Endianness {
+    /// least significant byte first
+    Little,
+    /// most significant byte first
+    Big,
+}
+
+/// Integer type, g.e. 16 bits unsigned little endian
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct Integer {
+    bytes: usize,
+    signed: bool,
+    endianness: Endianness,
+}
+
+impl Integer {
+    /// Builds an integer type with the provided number of bits, if 8, 16, 32 or 64
+    pub fn new(bits: usize, signed: bool, endianness: Endianness) -> Option<Self> {
+        match bits {
+            8 | 16 | 32 | 64 => Some(Integer {
+                bytes: bits / 8,
+                signed,
+                endianness,
+            }),
+            _ => None,
+        }
+    }
+
+    pub fn bits(self) -> usize {
+        8 * self.bytes
+    }
+
+    pub fn signed(self) -> bool {
+        self.signed
+    }
+
+    pub fn endianness(self) -> Endianness {
+        self.endianness
+    }
+
+    /// Bytes coding the provided value, whose bits beyond the type width are ignored
+    pub(crate) fn encode(self, value: u64) -> Vec<u8> {
+        let mut bytes = value.to_le_bytes()[..self.bytes].to_vec();
+        if self.endianness == Endianness::Big {
+            bytes.reverse();
+        }
+        bytes
+    }
+
+    /// Value coded by the provided bytes, zero extended
+    pub(crate) fn decode(self, bytes: &[u8]) -> u64 {
+        let mut le = [0u8; 8];
+        le[..self.bytes].copy_from_slice(bytes);
+        if self.endianness == Endianness::Big {
+            le[..self.bytes].reverse();
+        }
+        u64::from_le_bytes(le)
+    }
+
+    /// Parses the provided token as a value of this type, see [Reader]
+    pub(crate) fn parse(self, token: &[u8], notation: Notation) -> Result<u64, InError> {
+        let (negative, digits) = match token {
+            [b'-', digits @ ..] => (true, digits),
+            [b'+', digits @ ..] => (false, digits),
+            _ => (false, token),
+        };
+        let (radix, digits) = match digits.strip_prefix(b"0x").or(digits.strip_prefix(b"0X")) {
+            Some(digits) => (16, digits),
+            None if notation == Notation::Hexadecimal => (16, digits),
+            None => (10, digits),
+        };
+        if digits.is_empty() {
+            return Err(InError::InvalidByte(*token.last().unwrap() as char));
+        }
+        let mut magnitude = 0u64;
+        for &d in digits {
+            let digit = (d as char)
+                .to_digit(radix)
+                .ok_or(InError::InvalidByte(d as char))?;
+            magnitude = magnitude
+                .checked_mul(radix as u64)
+                .and_then(|m| m.checked_add(digit as u64))
+                .ok_or(InError::OutOfRange)?;
+        }
+        let bits = self.bits() as u32;
+        let max = u64::MAX >> (64 - bits);
+        let signed_max = max >> 1;
+        if negative {
+            if magnitude > 0 && (!self.signed || magnitude > signed_max + 1) {
+                return Err(InError::OutOfRange);
+            }
+            Ok(magnitude.wrapping_neg())
+        } else if magnitude > max || (self.signed && radix == 10 && magnitude > signed_max) {
+            Err(InError::OutOfRange)
+        } else {
+            Ok(magnitude)
+        }
+    }
+
+    /// Digits of the provided value of this type
+    pub(crate) fn format(self, value: u64, notation: Notation) -> String {
+        let bits = self.bits();
+        match notation {
+            Notation::Decimal if self.signed => {
+                let shift = 64 - bits;
+                (((value << shift) as i64) >> shift).to_string()
+            }
+            Notation::Decimal => value.to_string(),
+            Notation::Hexadecimal => format!("{value:0width$x}", width = bits / 4),
+        }
+    }
+}
+
+/// Notation of integer values
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum Notation {
+    /// decimal, with a leading '-' for negative values
+    #[default]
+    Decimal,
+    /// hexadecimal, with as many digits as needed by the type width; signed values in two's
+    /// complement (g.e. 'ffff' for -1 as 16 bits integer)
+    Hexadecimal,
+}
+
+/// An iterator over Result<u8,[InError]>
+///
+/// Reads a list of integer values of the provided [Integer] type from the input stream, returning
+/// the bytes coding each of them. Values are split by whitespace, ',', ';' or brackets; in
+/// [Decimal] notation they may begin with a sign, while '0x' prefixed values are hexadecimal in
+/// any notation.
+///
+/// Hexadecimal values code the bits of the integer, so they may be as large as the type width
+/// allows, even if signed (g.e. '0xffff' is read as -1 as 16 bits signed integer); values that
+/// can not be represented by the type are [OutOfRange].
+///
+/// [InError]: crate::error::InError
+/// [Decimal]: Notation::Decimal
+/// [OutOfRange]: crate::error::ErrorType::OutOfRange
+pub struct Reader<R: Read> {
+    tokens: Tokens<R>,
+    integer: Integer,
+    notation: Notation,
+}
+
+impl<R: Read> Reader<R> {
+    pub fn new(read: R, integer: Integer, notation: Notation) -> Self {
+        Reader {
+            tokens: Tokens::new(read),
+            integer,
+            notation,
+        }
+    }
+}
+
+impl<R: Read> Iterator for Reader<R> {
+    type Item = Result<u8, InError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let (integer, notation) = (self.integer, self.notation);
+        self.tokens
+            .next_byte(|token| Ok(integer.encode(integer.parse(token, notation)?)))
+    }
+}
+
+impl<R: Read> ByteReader for Reader<R> {
+    fn location(&self) -> Location {
+        self.tokens.location()
+    }
+}
+
+/// Writes bytes to the output stream as a list of integer values of the provided [Integer] type
+///
+/// Every value is coded by as many bytes as the type width: a trailing partial value is a
+/// [ShortIO] error on [finish]. Values are written according to the provided [Notation] and
+/// [Format], where a single space is used if the separator is empty.
+///
+/// [ShortIO]: crate::error::ErrorType::ShortIO
+/// [finish]: crate::byte_writer::ByteWriter::finish
+/// [Format]: crate::text::Format
+pub struct Writer<W: Write> {
+    out_bytes: W,
+    integer: Integer,
+    notation: Notation,
+    formatter: Formatter,
+    bytes: Vec<u8>,
+}
+
+impl<W: Write> Writer<W> {
+    pub fn new(out_bytes: W, integer: Integer, notation: Notation) -> Self {
+        Self::with_format(out_bytes, integer, notation, Format::default())
+    }
+
+    pub fn with_format(out_bytes: W, integer: Integer, notation: Notation, format: Format) -> Self {
+        let format = format.with_default_separator();
+        Writer {
+            out_bytes,
+            integer,
+            notation,
+            formatter: Formatter::new(format),
+            bytes: Vec::with_capacity(integer.bytes),
+        }
+    }
+}
+
+impl<W: Write> ByteWriter for Writer<W> {
+    fn write(&mut self, byte: u8) -> Result<(), OutError> {
+        self.bytes.push(byte);
+        if self.bytes.len() < self.integer.bytes {
+            return Ok(());
+        }
+        let value = self.integer.decode(&self.bytes);
+        self.bytes.clear();
+        let digits = self.integer.format(value, self.notation);
+        self.formatter.write(&mut self.out_bytes, digits.as_bytes())
+    }
+
+    fn finish(&mut self) -> Result<(), OutError> {
+        if !self.bytes.is_empty() {
+            return Err(OutError::ShortIO {
+                bytes: self.bytes.len(),
+                expected: self.integer.bytes,
+            });
+        }
+        self.formatter.finish(&mut self.out_bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn integer(bits: usize, signed: bool, endianness: Endianness) -> Integer {
+        Integer::new(bits, signed, endianness).unwrap()
+    }
+
+    fn decode(input: &str, integer: Integer, notation: Notation) -> Vec<Result<u8, InError>> {
+        Reader::new(input.as_bytes(), integer, notation).collect()
+    }
+
+    fn encode(input: &[u8], integer: Integer, notation: Notation) -> String {
+        let mut output = vec![];
+        let mut writer = Writer::new(&mut output, integer, notation);
+        for b in input {
+            writer.write(*b).unwrap();
+        }
+        writer.finish().unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn read() {
+        let u16le = integer(16, false, Endianness::Little);
+        let bytes = decode("513, 0x1234\n[65535]", u16le, Notation::Decimal);
+        assert!(matches!(
+            bytes[..],
+            [Ok(1), Ok(2), Ok(0x34), Ok(0x12), Ok(0xff), Ok(0xff)]
+        ));
+        let i32be = integer(32, true, Endianness::Big);
+        let bytes = decode("-2 +1", i32be, Notation::Decimal);
+        assert!(matches!(
+            bytes[..],
+            [
+                Ok(0xff),
+                Ok(0xff),
+                Ok(0xff),
+                Ok(0xfe),
+                Ok(0),
+                Ok(0),
+                Ok(0),
+                Ok(1)
+            ]
+        ));
+        let i16be = integer(16, true, Endianness::Big);
+        let bytes = decode("fffe 7f", i16be, Notation::Hexadecimal);
+        assert!(matches!(bytes[..], [Ok(0xff), Ok(0xfe), Ok(0), Ok(0x7f)]));
+        let i64le = integer(64, true, Endianness::Little);
+        let bytes = decode("-9223372036854775808", i64le, Notation::Decimal);
+        assert!(matches!(bytes[..], [.., Ok(0x80)]));
+    }
+
+    #[test]
+    fn read_invalid() {
+        let u16le = integer(16, false, Endianness::Little);
+        let bytes = decode("65536 -1 1a 0x 1", u16le, Notation::Decimal);
+        assert!(matches!(
+            bytes[..],
+            [
+                Err(InError::OutOfRange),
+                Err(InError::OutOfRange),
+                Err(InError::InvalidByte('a')),
+                Err(InError::InvalidByte('x')),
+                Ok(1),
+                Ok(0)
+            ]
+        ));
+        let i8 = integer(8, true, Endianness::Little);
+        let bytes = decode(
+            "128 -128 -129 0x80 99999999999999999999",
+            i8,
+            Notation::Decimal,
+        );
+        assert!(matches!(
+            bytes[..],
+            [
+                Err(InError::OutOfRange),
+                Ok(0x80),
+                Err(InError::OutOfRange),
+                Ok(0x80),
+                Err(InError::OutOfRange)
+            ]
+        ));
+        let mut reader = Reader::new("1 -1".as_bytes(), u16le, Notation::Decimal);
+        reader.next().unwrap().unwrap();
+        reader.next().unwrap().unwrap();
+        assert!(reader.next().unwrap().is_err());
+        assert_eq!(3, reader.location().offset);
+        let mut reader = Reader::new("70000 ".as_bytes(), u16le, Notation::Decimal);
+        assert!(reader.next().unwrap().is_err());
+        assert_eq!(4, reader.location().offset);
+    }
+
+    #[test]
+    fn write() {
+        let input = [0x01, 0x02, 0xff, 0xff];
+        let u16le = integer(16, false, Endianness::Little);
+        assert_eq!("513 65535", encode(&input, u16le, Notation::Decimal));
+        let i16be = integer(16, true, Endianness::Big);
+        assert_eq!("258 -1", encode(&input, i16be, Notation::Decimal));
+        assert_eq!("0102 ffff", encode(&input, i16be, Notation::Hexadecimal));
+        let i32le = integer(32, true, Endianness::Little);
+        assert_eq!("-65023", encode(&input, i32le, Notation::Decimal));
+        let u64be = integer(64, false, Endianness::Big);
+        assert_eq!(
+            "18446744073709551615",
+            encode(&[0xff; 8], u64be, Notation::Decimal)
+        );
+        let mut output = vec![];
+        let mut writer = Writer::new(&mut output, i32le, Notation::Decimal);
+        writer.write(0).unwrap();
+        assert!(matches!(
+            writer.finish(),
+            Err(OutError::ShortIO {
+                bytes: 1,
+                expected: 4
+            })
+        ));
+    }
+
+    #[test]
+    fn roundtrip() {
+        let input: Vec<u8> = (0..=255).collect();
+        for bits in [8, 16, 32, 64] {
+            for signed in [false, true] {
+                for endianness in [Endianness::Little, Endianness::Big] {
+                    for notation in [Notation::Decimal, Notation::Hexadecimal] {
+                        let integer = integer(bits, signed, endianness);
+                        let output = encode(&input, integer, notation);
+                        let bytes = decode(&output, integer, notation);
+                        assert_eq!(
+                            input,
+                            bytes.into_iter().map(|b| b.unwrap()).collect::<Vec<_>>()
+                        );
+                    }
+                }
+            }
+        }
+        assert!(Integer::new(24, false, Endianness::Little).is_none());
+    }
+}
@@ -0,0 +1,335 @@
+use crate::byte_reader::ByteReader;
+use crate::byte_writer::ByteWriter;
+use crate::error::{InError, Location, OutError};
+use crate::integer::{Endianness, Integer, Notation};
+use crate::text::{Format, Formatter, Tokens};
+use std::io::{Read, Write};
+
+/// Maximum number of bytes coding a 64 bits value
+const MAX_BYTES: usize = 10;
+
+/// LEB128 (Little Endian Base 128) variable length encoding of 64 bits integers
+///
+/// Every byte codes 7 bits of the value, least significant first, with the most significant bit
+/// set if more bytes follow (g.e. 300 is coded as 0xac 0x02).
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Encoding {
+    /// unsigned values (protobuf uint64, WebAssembly u32/u64)
+    Unsigned,
+    /// signed values in two's complement, sign extended from the last byte (WebAssembly s32/s64,
+    /// DWARF)
+    Signed,
+    /// signed values mapped to unsigned ones (0, -1, 1, -2... to 0, 1, 2, 3...) before encoding
+    /// (protobuf sint64)
+    ZigZag,
+}
+
+impl Encoding {
+    /// Type of the coded values
+    fn integer(self) -> Integer {
+        let signed = self != Encoding::Unsigned;
+        Integer::new(64, signed, Endianness::Little).unwrap()
+    }
+
+    /// Bytes coding the provided value (bits of an i64 for signed encodings)
+    fn encode(self, value: u64) -> Vec<u8> {
+        let mut bytes = vec![];
+        match self {
+            Encoding::Unsigned | Encoding::ZigZag => {
+                let mut value = match self {
+                    Encoding::ZigZag => (value << 1) ^ (((value as i64) >> 63) as u64),
+                    _ => value,
+                };
+                while value >= 0x80 {
+                    bytes.push(value as u8 | 0x80);
+                    value >>= 7;
+                }
+                bytes.push(value as u8);
+            }
+            Encoding::Signed => {
+                let mut value = value as i64;
+                // last byte when the remaining bits are just the sign extension of bit 6
+                while !(-0x40..0x40).contains(&value) {
+                    bytes.push(value as u8 | 0x80);
+                    value >>= 7;
+                }
+                bytes.push(value as u8 & 0x7f);
+            }
+        }
+        bytes
+    }
+
+    /// Value coded by the provided bytes, the last one only without the continuation bit
+    fn decode(self, bytes: &[u8]) -> Result<u64, OutError> {
+        if let [.., previous, last] = bytes {
+            let redundant = match self {
+                Encoding::Signed => {
+                    (*last == 0 && previous & 0x40 == 0) || (*last == 0x7f && previous & 0x40 != 0)
+                }
+                _ => *last == 0,
+            };
+            if redundant {
+                return Err(OutError::Overlong);
+            }
+        }
+        if bytes.len() > MAX_BYTES {
+            return Err(OutError::OutOfRange);
+        }
+        let value = bytes
+            .iter()
+            .rev()
+            .fold(0u128, |value, b| (value << 7) | (b & 0x7f) as u128);
+        match self {
+            Encoding::Unsigned | Encoding::ZigZag => {
+                let value = u64::try_from(value).map_err(|_| OutError::OutOfRange)?;
+                match self {
+                    Encoding::ZigZag => Ok((value >> 1) ^ (value & 1).wrapping_neg()),
+                    _ => Ok(value),
+                }
+            }
+            Encoding::Signed => {
+                let shift = 128 - 7 * bytes.len() as u32;
+                let value = ((value << shift) as i128) >> shift;
+                let value = i64::try_from(value).map_err(|_| OutError::OutOfRange)?;
+                Ok(value as u64)
+            }
+        }
+    }
+}
+
+/// An iterator over Result<u8,[InError]>
+///
+/// Reads a list of 64 bits integer values from the input stream, returning the bytes coding each
+/// of them with the provided LEB128 [Encoding]. Values are split by whitespace, ',', ';' or
+/// brackets and may be written in decimal (with a sign, for signed encodings) or in hexadecimal
+/// with '0x' prefix, as [integer] values.
+///
+/// [InError]: crate::error::InError
+/// [integer]: crate::integer::Reader
+pub struct Reader<R: Read> {
+    tokens: Tokens<R>,
+    encoding: Encoding,
+    notation: Notation,
+}
+
+impl<R: Read> Reader<R> {
+    pub fn new(read: R, encoding: Encoding, notation: Notation) -> Self {
+        Reader {
+            tokens: Tokens::new(read),
+            encoding,
+            notation,
+        }
+    }
+}
+
+impl<R: Read> Iterator for Reader<R> {
+    type Item = Result<u8, InError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let (encoding, notation) = (self.encoding, self.notation);
+        self.tokens
+            .next_byte(|token| Ok(encoding.encode(encoding.integer().parse(token, notation)?)))
+    }
+}
+
+impl<R: Read> ByteReader for Reader<R> {
+    fn location(&self) -> Location {
+        self.tokens.location()
+    }
+}
+
+/// Writes bytes to the output stream as the list of values they code with the provided LEB128
+/// [Encoding]
+///
+/// Values are written according to the provided [Notation] and [Format] (where a single space is
+/// used if the separator is empty), as 64 bits [integer] values.
+///
+/// A value coded by more bytes than needed is an [Overlong] error, a value beyond 64 bits is
+/// [OutOfRange]; a trailing partial value is a [ShortIO] error on [finish].
+///
+/// [Format]: crate::text::Format
+/// [integer]: crate::integer::Writer
+/// [Overlong]: crate::error::ErrorType::Overlong
+/// [OutOfRange]: crate::error::ErrorType::OutOfRange
+/// [ShortIO]: crate::error::ErrorType::ShortIO
+/// [finish]: crate::byte_writer::ByteWriter::finish
+pub struct Writer<W: Write> {
+    out_bytes: W,
+    encoding: Encoding,
+    notation: Notation,
+    formatter: Formatter,
+    bytes: Vec<u8>,
+}
+
+impl<W: Write> Writer<W> {
+    pub fn new(out_bytes: W, encoding: Encoding, notation: Notation) -> Self {
+        Self::with_format(out_bytes, encoding, notation, Format::default())
+    }
+
+    pub fn with_format(
+        out_bytes: W,
+        encoding: Encoding,
+        notation: Notation,
+        format: Format,
+    ) -> Self {
+        let format = format.with_default_separator();
+        Writer {
+            out_bytes,
+            encoding,
+            notation,
+            formatter: Formatter::new(format),
+            bytes: vec![],
+        }
+    }
+}
+
+impl<W: Write> ByteWriter for Writer<W> {
+    fn write(&mut self, byte: u8) -> Result<(), OutError> {
+        self.bytes.push(byte);
+        if byte & 0x80 != 0 {
+            return if self.bytes.len() > MAX_BYTES {
+                Err(OutError::OutOfRange)
+            } else {
+                Ok(())
+            };
+        }
+        let bytes = std::mem::take(&mut self.bytes);
+        let value = self.encoding.decode(&bytes)?;
+        let digits = self.encoding.integer().format(value, self.notation);
+        self.formatter.write(&mut self.out_bytes, digits.as_bytes())
+    }
+
+    fn finish(&mut self) -> Result<(), OutError> {
+        if !self.bytes.is_empty() {
+            return Err(OutError::ShortIO {
+                bytes: self.bytes.len(),
+                expected: self.bytes.len() + 1,
+            });
+        }
+        self.formatter.finish(&mut self.out_bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(input: &str, encoding: Encoding) -> Vec<u8> {
+        Reader::new(input.as_bytes(), encoding, Notation::Decimal)
+            .map(|b| b.unwrap())
+            .collect()
+    }
+
+    fn encode(input: &[u8], encoding: Encoding) -> Result<String, OutError> {
+        let mut output = vec![];
+        let mut writer = Writer::new(&mut output, encoding, Notation::Decimal);
+        for b in input {
+            writer.write(*b)?;
+        }
+        writer.finish()?;
+        Ok(String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn read() {
+        assert_eq!(
+            [0, 0x7f, 0xac, 0x02, 0xe5, 0x8e, 0x26].as_slice(),
+            decode("0 127 300 624485", Encoding::Unsigned)
+        );
+        assert_eq!(
+            [0x3f, 0x40, 0xc0, 0x00, 0xc0, 0xbb, 0x78].as_slice(),
+            decode("63 -64 64 -123456", Encoding::Signed)
+        );
+        assert_eq!(
+            [0x00, 0x01, 0x02, 0x03, 0xfe, 0x01].as_slice(),
+            decode("0 -1 1 -2 127", Encoding::ZigZag)
+        );
+        let max = decode("18446744073709551615", Encoding::Unsigned);
+        assert_eq!([0xff; 9].as_slice(), &max[..9]);
+        assert_eq!([0x01].as_slice(), &max[9..]);
+        let bytes: Vec<_> =
+            Reader::new("-1 x".as_bytes(), Encoding::Unsigned, Notation::Decimal).collect();
+        assert!(matches!(
+            bytes[..],
+            [Err(InError::OutOfRange), Err(InError::InvalidByte('x'))]
+        ));
+    }
+
+    #[test]
+    fn write() {
+        let input = [0, 0x7f, 0xac, 0x02, 0xe5, 0x8e, 0x26];
+        assert_eq!(
+            "0 127 300 624485",
+            encode(&input, Encoding::Unsigned).unwrap()
+        );
+        let input = [0x3f, 0x40, 0xc0, 0x00, 0xc0, 0xbb, 0x78];
+        assert_eq!(
+            "63 -64 64 -123456",
+            encode(&input, Encoding::Signed).unwrap()
+        );
+        let input = [0x00, 0x01, 0x02, 0x03, 0xfe, 0x01];
+        assert_eq!("0 -1 1 -2 127", encode(&input, Encoding::ZigZag).unwrap());
+        let mut input = vec![0x80; 9];
+        input.push(0x7f);
+        assert_eq!(
+            "-9223372036854775808",
+            encode(&input, Encoding::Signed).unwrap()
+        );
+    }
+
+    #[test]
+    fn write_invalid() {
+        assert!(matches!(
+            encode(&[0xac, 0x82], Encoding::Unsigned),
+            Err(OutError::ShortIO {
+                bytes: 2,
+                expected: 3
+            })
+        ));
+        assert!(matches!(
+            encode(&[0x80, 0x00], Encoding::Unsigned),
+            Err(OutError::Overlong)
+        ));
+        assert!(matches!(
+            encode(&[0xff, 0x7f], Encoding::Signed),
+            Err(OutError::Overlong)
+        ));
+        assert!(matches!(
+            encode(&[0xc0, 0x7f], Encoding::Signed),
+            Err(OutError::Overlong)
+        ));
+        assert!(matches!(
+            encode(&[0xbf, 0x7f], Encoding::Signed),
+            Ok(s) if s == "-65"
+        ));
+        let mut input = vec![0xff; 9];
+        input.push(0x02);
+        assert!(matches!(
+            encode(&input, Encoding::Unsigned),
+            Err(OutError::OutOfRange)
+        ));
+        input[9] = 0x01;
+        assert!(matches!(
+            encode(&input, Encoding::Signed),
+            Err(OutError::OutOfRange)
+        ));
+        assert!(matches!(
+            encode(&[0x80; 11], Encoding::Unsigned),
+            Err(OutError::OutOfRange)
+        ));
+    }
+
+    #[test]
+    fn roundtrip() {
+        for encoding in [Encoding::Unsigned, Encoding::Signed, Encoding::ZigZag] {
+            let mut values: Vec<String> = vec![];
+            for shift in 0..64 {
+                let value = 1u64 << shift;
+                for v in [value, value - 1, value.wrapping_neg()] {
+                    values.push(encoding.integer().format(v, Notation::Decimal));
+                }
+            }
+            let input = values.join(" ");
+            assert_eq!(input, encode(&decode(&input, encoding), encoding).unwrap());
+        }
+    }
+}
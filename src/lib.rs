@@ -3,26 +3,52 @@
 pub mod error;
 use error::*;
 
+pub mod byte_reader;
+use byte_reader::ByteReader;
+
 pub mod byte_writer;
 use byte_writer::ByteWriter;
 
+pub mod array;
 pub mod ascii;
+pub mod ascii85;
 pub mod base;
+pub mod base32;
+pub mod base64;
 pub mod binary;
+pub mod codepage;
+pub mod escaped;
+pub mod float;
 pub mod hexadecimal;
+pub mod hexdump;
+pub mod ihex;
+pub mod integer;
+pub mod leb128;
+pub mod percent;
+pub mod quoted_printable;
+pub mod radix;
 pub mod raw;
+pub mod srec;
+pub mod text;
+pub mod unicode;
+pub mod utf8;
+pub mod uuencode;
 
+mod image;
 mod util;
 
 /// Converts byte input stream format to byte output stream format
 ///
-/// Iterates on bytes in istream and [writes] them to ostream.
+/// Iterates on bytes in istream and [writes] them to ostream; once istream is exhausted, ostream
+/// is [finished].
 ///
 /// [writes]: crate::byte_writer::ByteWriter::write
+/// [finished]: crate::byte_writer::ByteWriter::finish
 ///
 /// # Errors
 ///
-/// see [ErrorType] for error details.
+/// see [ErrorType] for error details; since istream is any iterator, input errors are not located
+/// (see [convert_with] for located input errors).
 ///
 /// [ErrorType]: crate::error::ErrorType
 ///
@@ -51,19 +77,85 @@ where
     I: Iterator<Item = Result<u8, InError>> + ?Sized,
     O: ByteWriter + ?Sized,
 {
-    for input in istream {
-        match input {
-            Ok(input) => {
-                if let Err(out_error) = ostream.write(input) {
-                    return Err(Error::Out(out_error));
-                }
+    transfer(istream, ostream, OnError::Fail, |_| None).map(|_| ())
+}
+
+/// Converts byte input stream format to byte output stream format, recovering from invalid input
+///
+/// Same as [convert], except that input errors are located by the [location] of the reader and
+/// (other than I/O errors) handled according to the provided [OnError] policy; returns the number
+/// of recovered input errors.
+///
+/// [location]: crate::byte_reader::ByteReader::location
+///
+/// # Examples
+///
+/// hexadecimal to ASCII conversion, replacing invalid digits with '?'
+/// ```
+/// use bread_cli::*;
+/// use bread_cli::error::OnError;
+///
+/// let input = b"41 4g 42";
+/// let mut output = [0u8; 3];
+/// let mut reader = hexadecimal::Reader::new(input.as_slice());
+/// let mut writer = ascii::Writer::new(output.as_mut_slice());
+/// let recovered = convert_with(&mut reader, &mut writer, OnError::Replace(b'?')).unwrap();
+/// assert_eq!(1, recovered);
+/// assert_eq!(b"A?B", &output);
+/// ```
+///
+pub fn convert_with<I, O>(
+    istream: &mut I,
+    ostream: &mut O,
+    on_error: OnError,
+) -> Result<usize, Error>
+where
+    I: ByteReader + ?Sized,
+    O: ByteWriter + ?Sized,
+{
+    transfer(istream, ostream, on_error, |istream| {
+        Some(istream.location())
+    })
+}
+
+/// Implementation of [convert] and [convert_with], locating input errors by the provided function
+fn transfer<I, O, L>(
+    istream: &mut I,
+    ostream: &mut O,
+    on_error: OnError,
+    location: L,
+) -> Result<usize, Error>
+where
+    I: Iterator<Item = Result<u8, InError>> + ?Sized,
+    O: ByteWriter + ?Sized,
+    L: Fn(&I) -> Option<Location>,
+{
+    let mut offset = 0usize;
+    let mut recovered = 0usize;
+    while let Some(input) = istream.next() {
+        let input = match (input, on_error) {
+            (Ok(input), _) => input,
+            (Err(e @ InError::StdIO(_)), _) | (Err(e), OnError::Fail) => {
+                return Err(Error::In(e, location(istream)));
+            }
+            (Err(_), OnError::Skip) => {
+                recovered += 1;
+                continue;
             }
-            Err(in_error) => {
-                return Err(Error::In(in_error));
+            (Err(_), OnError::Replace(byte)) => {
+                recovered += 1;
+                byte
             }
+        };
+        if let Err(out_error) = ostream.write(input) {
+            return Err(Error::Out(out_error, offset));
         }
+        offset += 1;
     }
-    Ok(())
+    ostream
+        .finish()
+        .map(|_| recovered)
+        .map_err(|e| Error::Out(e, offset))
 }
 
 #[cfg(test)]
@@ -201,6 +293,103 @@ mod tests {
         convert(&mut reader, &mut writer).unwrap();
         assert_eq!(expected, output);
     }
+
+    #[test]
+    fn convert_flushes() {
+        struct Flushed(usize);
+        impl std::io::Write for Flushed {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                Ok(buf.len())
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                self.0 += 1;
+                Ok(())
+            }
+        }
+        let input = [_A, _B];
+        let mut output = Flushed(0);
+        let mut reader = ascii::Reader::new(input.as_slice());
+        let mut writer = raw::Writer::new(&mut output);
+        convert(&mut reader, &mut writer).unwrap();
+        assert_eq!(1, output.0);
+    }
+
+    #[test]
+    fn convert_error_position() {
+        let input = b"41 42\n4g";
+        let mut output = [0u8; 4];
+        let mut reader = hexadecimal::Reader::new(input.as_slice());
+        let mut writer = ascii::Writer::new(output.as_mut_slice());
+        match convert_with(&mut reader, &mut writer, OnError::Fail) {
+            Err(Error::In(InError::InvalidByte('g'), location)) => assert_eq!(
+                Some(Location {
+                    offset: 7,
+                    line: 2,
+                    column: 2
+                }),
+                location
+            ),
+            _ => panic!("unexpected result"),
+        }
+        let input = [0x41, 0xff];
+        let mut reader = raw::Reader::new(input.as_slice());
+        let mut writer = ascii::Writer::new(output.as_mut_slice());
+        assert!(matches!(
+            convert(&mut reader, &mut writer),
+            Err(Error::Out(OutError::InvalidByte(0xff), 1))
+        ));
+    }
+
+    #[test]
+    fn convert_iterator() {
+        let input = [Ok(0x41), Ok(0x42), Err(InError::InvalidByte('g'))];
+        let mut output = vec![];
+        let mut writer = raw::Writer::new(&mut output);
+        assert!(matches!(
+            convert(&mut input.into_iter(), &mut writer),
+            Err(Error::In(InError::InvalidByte('g'), None))
+        ));
+        assert_eq!([0x41, 0x42].as_slice(), output);
+    }
+
+    #[test]
+    fn convert_on_error() {
+        let input = b"41g4\n2";
+        let mut output = vec![];
+        let mut reader = hexadecimal::Reader::new(input.as_slice());
+        let mut writer = raw::Writer::new(&mut output);
+        assert_eq!(
+            1,
+            convert_with(&mut reader, &mut writer, OnError::Skip).unwrap()
+        );
+        assert_eq!([0x41, 0x42].as_slice(), output);
+        let input = b"41g";
+        let mut output = vec![];
+        let mut reader = hexadecimal::Reader::new(input.as_slice());
+        let mut writer = raw::Writer::new(&mut output);
+        assert_eq!(
+            1,
+            convert_with(&mut reader, &mut writer, OnError::Replace(0)).unwrap()
+        );
+        assert_eq!([0x41, 0].as_slice(), output);
+        let mut reader = hexadecimal::Reader::new(input.as_slice());
+        let mut writer = raw::Writer::new(&mut output);
+        assert!(matches!(
+            convert_with(&mut reader, &mut writer, OnError::Fail),
+            Err(Error::In(InError::InvalidByte('g'), _))
+        ));
+    }
+
+    #[test]
+    fn hex_to_b64() {
+        let input = [_4, _D, _6, _1];
+        let mut output = [0u8; 4];
+        let expected = [b'T', b'W', b'E', b'='];
+        let mut reader = hexadecimal::Reader::new(input.as_slice());
+        let mut writer = base64::Writer::new(output.as_mut_slice(), base64::Alphabet::Standard);
+        convert(&mut reader, &mut writer).unwrap();
+        assert_eq!(expected, output);
+    }
 }
 
 #[cfg(all(test, feature = "benchmark"))]
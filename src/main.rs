@@ -1,10 +1,35 @@
 mod config;
+use bread::error::{Error, ErrorType};
 use bread_cli as bread;
 use config::Config;
+use std::process::ExitCode;
 
-fn main() {
-    let (mut input, mut output) = Config::new().unwrap().into();
-    if let Err(e) = bread::convert(input.as_mut(), output.as_mut()) {
-        eprintln!("{e:?}");
+// exit codes 1 and 2 are left to panics and command line parsing errors
+const INVALID_INPUT: u8 = 3;
+const SHORT_INPUT: u8 = 4;
+const OUTPUT_ERROR: u8 = 5;
+const IO_ERROR: u8 = 6;
+
+fn exit_code(e: &Error) -> u8 {
+    match e {
+        Error::In(ErrorType::StdIO(_), _) | Error::Out(ErrorType::StdIO(_), _) => IO_ERROR,
+        Error::In(ErrorType::ShortIO { .. }, _) => SHORT_INPUT,
+        Error::In(_, _) => INVALID_INPUT,
+        Error::Out(_, _) => OUTPUT_ERROR,
+    }
+}
+
+fn main() -> ExitCode {
+    let (mut input, mut output, on_error) = Config::new().unwrap().into();
+    match bread::convert_with(input.as_mut(), output.as_mut(), on_error) {
+        Ok(0) => ExitCode::SUCCESS,
+        Ok(recovered) => {
+            eprintln!("bread: {recovered} input errors recovered");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("bread: {e}");
+            ExitCode::from(exit_code(&e))
+        }
     }
 }
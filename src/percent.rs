@@ -0,0 +1,195 @@
+use crate::byte_reader::ByteReader;
+use crate::byte_writer::ByteWriter;
+use crate::error::{InError, Location, OutError};
+use crate::util;
+use std::io::{Read, Write};
+
+/// Set of characters written unchanged by percent-encoding, all the others being written as '%HH'
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Set {
+    /// RFC 3986 path: unreserved characters (letters, digits and '-._~'), sub-delimiters
+    /// ("!$&'()*+,;=") and ':@/'
+    Path,
+    /// RFC 3986 unreserved characters only, for a single URL component (g.e. a query key or
+    /// value)
+    Component,
+    /// application/x-www-form-urlencoded (HTML forms): letters, digits and '*-._', with space
+    /// written as '+'
+    Form,
+}
+
+impl Set {
+    /// Whether the provided byte is written unchanged
+    fn contains(self, byte: u8) -> bool {
+        let unreserved = byte.is_ascii_alphanumeric() || b"-._~".contains(&byte);
+        match self {
+            Set::Path => unreserved || b"!$&'()*+,;=:@/".contains(&byte),
+            Set::Component => unreserved,
+            Set::Form => byte.is_ascii_alphanumeric() || b"*-._".contains(&byte),
+        }
+    }
+}
+
+/// An iterator over Result<u8,[InError]>
+///
+/// Reads bytes from the input stream as percent-encoded text (g.e. '%E2%9C%93' for '✓'),
+/// returning the byte coded by every '%HH' escape sequence and any other byte unchanged; with
+/// [Form] set, '+' is read as a space.
+///
+/// An escape sequence interrupted by the end of input is a [ShortIO] error, one with a non
+/// hexadecimal digit is an [InvalidByte] error.
+///
+/// [InError]: crate::error::InError
+/// [Form]: Set::Form
+/// [ShortIO]: crate::error::ErrorType::ShortIO
+/// [InvalidByte]: crate::error::ErrorType::InvalidByte
+pub struct Reader<R: Read> {
+    in_bytes: util::Input<R>,
+    set: Set,
+}
+
+impl<R: Read> Reader<R> {
+    pub fn new(read: R, set: Set) -> Self {
+        Reader {
+            in_bytes: util::Input::new(read),
+            set,
+        }
+    }
+}
+
+impl<R: Read> Iterator for Reader<R> {
+    type Item = Result<u8, InError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.in_bytes.next()? {
+            Ok(b'%') => {}
+            Ok(b'+') if self.set == Set::Form => return Some(Ok(b' ')),
+            Ok(c) => return Some(Ok(c)),
+            Err(e) => return Some(Err(InError::StdIO(e))),
+        }
+        let mut value = 0;
+        for read in 1..3 {
+            let c = match self.in_bytes.next() {
+                Some(Ok(c)) => c,
+                Some(Err(e)) => return Some(Err(InError::StdIO(e))),
+                None => {
+                    return Some(Err(InError::ShortIO {
+                        bytes: read,
+                        expected: 3,
+                    }));
+                }
+            };
+            match (c as char).to_digit(16) {
+                Some(digit) => value = (value << 4) | digit as u8,
+                None => return Some(Err(InError::InvalidByte(c as char))),
+            }
+        }
+        Some(Ok(value))
+    }
+}
+
+impl<R: Read> ByteReader for Reader<R> {
+    fn location(&self) -> Location {
+        self.in_bytes.location()
+    }
+}
+
+/// Writes bytes to the output stream percent-encoded: bytes in the provided [Set] are written
+/// unchanged, the others as '%HH' with upper case hexadecimal digits (g.e. '%E2%9C%93' for '✓')
+pub struct Writer<W: Write> {
+    out_bytes: W,
+    set: Set,
+}
+
+impl<W: Write> Writer<W> {
+    pub fn new(out_bytes: W, set: Set) -> Self {
+        Writer { out_bytes, set }
+    }
+}
+
+impl<W: Write> ByteWriter for Writer<W> {
+    fn write(&mut self, byte: u8) -> Result<(), OutError> {
+        if self.set.contains(byte) {
+            util::write(&mut self.out_bytes, &[byte], 1)
+        } else if self.set == Set::Form && byte == b' ' {
+            util::write(&mut self.out_bytes, b"+", 1)
+        } else {
+            util::write(&mut self.out_bytes, format!("%{byte:02X}").as_bytes(), 3)
+        }
+    }
+
+    fn finish(&mut self) -> Result<(), OutError> {
+        util::flush(&mut self.out_bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(input: &[u8], set: Set) -> String {
+        let mut output = vec![];
+        let mut writer = Writer::new(&mut output, set);
+        for b in input {
+            writer.write(*b).unwrap();
+        }
+        writer.finish().unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn read() {
+        let output: Vec<u8> = Reader::new("a%E2%9c%93+b/c".as_bytes(), Set::Path)
+            .map(|b| b.unwrap())
+            .collect();
+        assert_eq!("a✓+b/c".as_bytes(), output);
+        let output: Vec<u8> = Reader::new("a+b%2B".as_bytes(), Set::Form)
+            .map(|b| b.unwrap())
+            .collect();
+        assert_eq!(b"a b+", output.as_slice());
+    }
+
+    #[test]
+    fn read_invalid() {
+        let bytes: Vec<_> = Reader::new("%4g%4".as_bytes(), Set::Component).collect();
+        assert!(matches!(
+            bytes[..],
+            [
+                Err(InError::InvalidByte('g')),
+                Err(InError::ShortIO {
+                    bytes: 2,
+                    expected: 3
+                })
+            ]
+        ));
+        let bytes: Vec<_> = Reader::new("%".as_bytes(), Set::Component).collect();
+        assert!(matches!(
+            bytes[..],
+            [Err(InError::ShortIO {
+                bytes: 1,
+                expected: 3
+            })]
+        ));
+    }
+
+    #[test]
+    fn write() {
+        let input = "a/b c?d=✓&e~".as_bytes();
+        assert_eq!("a/b%20c%3Fd=%E2%9C%93&e~", encode(input, Set::Path));
+        assert_eq!(
+            "a%2Fb%20c%3Fd%3D%E2%9C%93%26e~",
+            encode(input, Set::Component)
+        );
+        assert_eq!("a%2Fb+c%3Fd%3D%E2%9C%93%26e%7E", encode(input, Set::Form));
+    }
+
+    #[test]
+    fn roundtrip() {
+        let input: Vec<u8> = (0..=255).collect();
+        for set in [Set::Path, Set::Component, Set::Form] {
+            let output: Vec<u8> = Reader::new(encode(&input, set).as_bytes(), set)
+                .map(|b| b.unwrap())
+                .collect();
+            assert_eq!(input, output);
+        }
+    }
+}
@@ -0,0 +1,317 @@
+use crate::byte_reader::ByteReader;
+use crate::byte_writer::ByteWriter;
+use crate::error::{InError, Location, OutError};
+use crate::util;
+use std::collections::VecDeque;
+use std::io::{Read, Write};
+
+/// Maximum number of characters per encoded line, soft line break included
+const LINE_LENGTH: usize = 76;
+
+/// An iterator over Result<u8,[InError]>
+///
+/// Reads bytes from the input stream in quoted-printable format, as defined in
+/// [RFC 2045](https://www.rfc-editor.org/rfc/rfc2045): every '=HH' escape sequence codes the
+/// byte with hexadecimal value HH, '=' at the end of a line is a soft line break (not part of
+/// the data), while any other character codes itself. Whitespace at the end of a line is
+/// ignored, as added in transport.
+///
+/// An escape sequence interrupted by the end of input is a [ShortIO] error, one with a non
+/// hexadecimal digit is an [InvalidByte] error.
+///
+/// [InError]: crate::error::InError
+/// [ShortIO]: crate::error::ErrorType::ShortIO
+/// [InvalidByte]: crate::error::ErrorType::InvalidByte
+pub struct Reader<R: Read> {
+    in_bytes: util::Input<R>,
+    lookahead: Option<u8>,
+    bytes: VecDeque<u8>,
+}
+
+impl<R: Read> Reader<R> {
+    pub fn new(read: R) -> Self {
+        Reader {
+            in_bytes: util::Input::new(read),
+            lookahead: None,
+            bytes: VecDeque::new(),
+        }
+    }
+
+    fn read(&mut self) -> Option<Result<u8, InError>> {
+        match self.lookahead.take() {
+            Some(c) => Some(Ok(c)),
+            None => Some(self.in_bytes.next()?.map_err(InError::StdIO)),
+        }
+    }
+
+    /// Reads the escape sequence or soft line break following '=' into self.bytes
+    fn read_escape(&mut self) -> Result<(), InError> {
+        let c = match self.read() {
+            Some(c) => c?,
+            None => {
+                return Err(InError::ShortIO {
+                    bytes: 1,
+                    expected: 3,
+                });
+            }
+        };
+        if let b' ' | b'\t' | b'\r' | b'\n' = c {
+            // soft line break, possibly after transport padding
+            let mut c = c;
+            while c != b'\n' {
+                c = match self.read() {
+                    Some(Ok(c @ (b' ' | b'\t' | b'\r' | b'\n'))) => c,
+                    Some(Ok(c)) => return Err(InError::InvalidByte(c as char)),
+                    Some(Err(e)) => return Err(e),
+                    None => break,
+                };
+            }
+            return Ok(());
+        }
+        let high = (c as char)
+            .to_digit(16)
+            .ok_or(InError::InvalidByte(c as char))?;
+        let c = match self.read() {
+            Some(c) => c?,
+            None => {
+                return Err(InError::ShortIO {
+                    bytes: 2,
+                    expected: 3,
+                });
+            }
+        };
+        let low = (c as char)
+            .to_digit(16)
+            .ok_or(InError::InvalidByte(c as char))?;
+        self.bytes.push_back((high << 4 | low) as u8);
+        Ok(())
+    }
+
+    /// Reads the whitespace following the provided one into self.bytes, unless at the end of a
+    /// line
+    fn read_whitespace(&mut self, first: u8) -> Result<(), InError> {
+        let mut whitespace = vec![first];
+        loop {
+            match self.read() {
+                Some(Ok(c @ (b' ' | b'\t'))) => whitespace.push(c),
+                Some(Ok(c @ (b'\r' | b'\n'))) => {
+                    self.lookahead = Some(c);
+                    return Ok(());
+                }
+                Some(Ok(c)) => {
+                    self.bytes.extend(whitespace);
+                    self.lookahead = Some(c);
+                    return Ok(());
+                }
+                Some(Err(e)) => return Err(e),
+                None => return Ok(()),
+            }
+        }
+    }
+}
+
+impl<R: Read> Iterator for Reader<R> {
+    type Item = Result<u8, InError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(b) = self.bytes.pop_front() {
+                return Some(Ok(b));
+            }
+            let c = match self.read()? {
+                Ok(c) => c,
+                Err(e) => return Some(Err(e)),
+            };
+            let read = match c {
+                b'=' => self.read_escape(),
+                b' ' | b'\t' => self.read_whitespace(c),
+                _ => return Some(Ok(c)),
+            };
+            if let Err(e) = read {
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
+impl<R: Read> ByteReader for Reader<R> {
+    fn location(&self) -> Location {
+        self.in_bytes.location()
+    }
+}
+
+/// Writes bytes to the output stream in quoted-printable format, as defined in
+/// [RFC 2045](https://www.rfc-editor.org/rfc/rfc2045)
+///
+/// Printable ASCII characters other than '=' are written unchanged, as well as spaces and tabs
+/// unless at the end of a line; any other byte is written as '=HH' (g.e. '=3D' for '='). Line
+/// breaks ('\n' or "\r\n") are kept, while lines longer than 76 characters are split by soft line
+/// breaks ('=' at the end of a line, followed by the same line break as the previous line).
+///
+/// Since every byte is encoded according to the following one, the last byte is only written on
+/// [finish].
+///
+/// [finish]: crate::byte_writer::ByteWriter::finish
+pub struct Writer<W: Write> {
+    out_bytes: W,
+    pending: Option<u8>,
+    column: usize,
+    crlf: bool,
+    line_break: &'static [u8],
+}
+
+impl<W: Write> Writer<W> {
+    pub fn new(out_bytes: W) -> Self {
+        Writer {
+            out_bytes,
+            pending: None,
+            column: 0,
+            crlf: false,
+            line_break: b"\n",
+        }
+    }
+
+    /// Writes the provided token, preceded by a soft line break if it does not fit in the line
+    fn write_token(&mut self, token: &[u8], last: bool) -> Result<(), OutError> {
+        // room for the soft line break, unless last
+        let length = if last { LINE_LENGTH } else { LINE_LENGTH - 1 };
+        if self.column + token.len() > length {
+            util::write(&mut self.out_bytes, b"=", 1)?;
+            util::write(&mut self.out_bytes, self.line_break, self.line_break.len())?;
+            self.column = 0;
+        }
+        self.column += token.len();
+        util::write(&mut self.out_bytes, token, token.len())
+    }
+
+    /// Encodes the provided byte, followed by next (None at the end of input)
+    fn encode(&mut self, byte: u8, next: Option<u8>) -> Result<(), OutError> {
+        let last = matches!(next, None | Some(b'\n'));
+        match byte {
+            b'\r' if next == Some(b'\n') => {
+                self.crlf = true;
+                Ok(())
+            }
+            b'\n' => {
+                self.line_break = if std::mem::take(&mut self.crlf) {
+                    b"\r\n"
+                } else {
+                    b"\n"
+                };
+                self.column = 0;
+                util::write(&mut self.out_bytes, self.line_break, self.line_break.len())
+            }
+            b'!'..=b'~' if byte != b'=' => self.write_token(&[byte], last),
+            b' ' | b'\t' if !matches!(next, None | Some(b'\r' | b'\n')) => {
+                self.write_token(&[byte], false)
+            }
+            _ => self.write_token(format!("={byte:02X}").as_bytes(), last),
+        }
+    }
+}
+
+impl<W: Write> ByteWriter for Writer<W> {
+    fn write(&mut self, byte: u8) -> Result<(), OutError> {
+        match self.pending.replace(byte) {
+            Some(pending) => self.encode(pending, Some(byte)),
+            None => Ok(()),
+        }
+    }
+
+    fn finish(&mut self) -> Result<(), OutError> {
+        if let Some(pending) = self.pending.take() {
+            self.encode(pending, None)?;
+        }
+        util::flush(&mut self.out_bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(input: &[u8]) -> String {
+        let mut output = vec![];
+        let mut writer = Writer::new(&mut output);
+        for b in input {
+            writer.write(*b).unwrap();
+        }
+        writer.finish().unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    fn decode(input: &str) -> Vec<u8> {
+        Reader::new(input.as_bytes()).map(|b| b.unwrap()).collect()
+    }
+
+    #[test]
+    fn read() {
+        assert_eq!(
+            "a=b ✓\r\nc\td".as_bytes(),
+            decode("a=3Db =E2=9c=93  \r\nc\t=\nd")
+        );
+        assert_eq!(b"ab".as_slice(), decode("a= \t\r\nb"));
+        assert_eq!(b"a\n".as_slice(), decode("a \n"));
+    }
+
+    #[test]
+    fn read_invalid() {
+        let bytes: Vec<_> = Reader::new("=4g=x=".as_bytes()).collect();
+        assert!(matches!(
+            bytes[..],
+            [
+                Err(InError::InvalidByte('g')),
+                Err(InError::InvalidByte('x')),
+                Err(InError::ShortIO {
+                    bytes: 1,
+                    expected: 3
+                })
+            ]
+        ));
+        let bytes: Vec<_> = Reader::new("=A".as_bytes()).collect();
+        assert!(matches!(
+            bytes[..],
+            [Err(InError::ShortIO {
+                bytes: 2,
+                expected: 3
+            })]
+        ));
+    }
+
+    #[test]
+    fn write() {
+        assert_eq!(
+            "a=3Db =E2=9C=93=20\r\nc\td=09",
+            encode("a=b ✓ \r\nc\td\t".as_bytes())
+        );
+        assert_eq!("=0D=00\n\n", encode(b"\r\0\n\n"));
+    }
+
+    #[test]
+    fn write_long_lines() {
+        let input = [b'a'; 76];
+        assert_eq!(String::from_utf8(input.to_vec()).unwrap(), encode(&input));
+        let input = [b'a'; 77];
+        let expected = format!("{}=\naa", "a".repeat(75));
+        assert_eq!(expected, encode(&input));
+        let mut input = vec![b'a'; 74];
+        input.push(b'=');
+        input.push(b'b');
+        let expected = format!("{}=\n=3Db", "a".repeat(74));
+        assert_eq!(expected, encode(&input));
+        let mut input = b"x\r\n".to_vec();
+        input.extend([b'a'; 77]);
+        let expected = format!("x\r\n{}=\r\naa", "a".repeat(75));
+        assert_eq!(expected, encode(&input));
+        for line in encode(&[0xff; 100]).lines() {
+            assert!(line.len() <= LINE_LENGTH);
+        }
+    }
+
+    #[test]
+    fn roundtrip() {
+        let mut input: Vec<u8> = (0..=255).collect();
+        input.extend(b"  \n\t\r\n");
+        input.extend([b' '; 100]);
+        assert_eq!(input, decode(&encode(&input)));
+    }
+}
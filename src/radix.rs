@@ -0,0 +1,269 @@
+use crate::byte_reader::ByteReader;
+use crate::byte_writer::ByteWriter;
+use crate::error::{InError, Location, OutError};
+use crate::util;
+use std::io::{Read, Write};
+
+/// Alphabet for whole-stream radix conversion
+///
+/// The radix is the number of symbols; the first symbol is the zero digit.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Alphabet {
+    symbols: Vec<u8>,
+}
+
+impl Alphabet {
+    /// Builds an alphabet out of the provided symbols
+    ///
+    /// Returns None unless symbols are at least 2, unique and printable ASCII characters other
+    /// than space
+    pub fn new(symbols: &str) -> Option<Self> {
+        let symbols = symbols.as_bytes().to_vec();
+        let unique = symbols
+            .iter()
+            .enumerate()
+            .all(|(i, s)| !symbols[..i].contains(s));
+        if symbols.len() > 1 && unique && symbols.iter().all(|s| s.is_ascii_graphic()) {
+            Some(Alphabet { symbols })
+        } else {
+            None
+        }
+    }
+    /// Bitcoin base58 alphabet
+    pub fn base58() -> Self {
+        Self::new("123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz").unwrap()
+    }
+    /// Flickr base58 alphabet
+    pub fn base58_flickr() -> Self {
+        Self::new("123456789abcdefghijkmnopqrstuvwxyzABCDEFGHJKLMNPQRSTUVWXYZ").unwrap()
+    }
+    /// base62 alphabet: '0'-'9', 'A'-'Z' and 'a'-'z'
+    pub fn base62() -> Self {
+        Self::new("0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz").unwrap()
+    }
+    fn radix(&self) -> u32 {
+        self.symbols.len() as u32
+    }
+    fn valid(&self, c: u8) -> Option<u32> {
+        self.symbols.iter().position(|&s| s == c).map(|d| d as u32)
+    }
+}
+
+/// An iterator over Result<u8,[InError]>
+///
+/// Reads the whole input stream as a single number written with the digits of the provided
+/// [Alphabet] (g.e. base58), then yields its bytes in big endian order; every leading zero digit
+/// is read as a leading zero byte. Any whitespace character is allowed and skipped.
+///
+/// Since the whole input is needed to compute the first byte, input is buffered. Invalid symbols
+/// are reported as they are read, without interrupting the decoding of the following ones.
+///
+/// [InError]: crate::error::InError
+pub struct Reader<R: Read> {
+    in_bytes: util::Input<R>,
+    alphabet: Alphabet,
+    zeros: usize,
+    value: Vec<u8>, // little endian
+    decoded: Option<std::vec::IntoIter<u8>>,
+}
+
+impl<R: Read> Reader<R> {
+    pub fn new(read: R, alphabet: Alphabet) -> Self {
+        Reader {
+            in_bytes: util::Input::new(read),
+            alphabet,
+            zeros: 0,
+            value: vec![],
+            decoded: None,
+        }
+    }
+
+    /// Decodes the input stream into self.zeros and self.value, up to its end or to the next
+    /// invalid symbol
+    fn decode(&mut self) -> Result<(), InError> {
+        for c in self.in_bytes.by_ref() {
+            let c = c.map_err(InError::StdIO)?;
+            if c.is_ascii_whitespace() {
+                continue;
+            }
+            let mut carry = self
+                .alphabet
+                .valid(c)
+                .ok_or(InError::InvalidByte(c as char))?;
+            if carry == 0 && self.value.is_empty() {
+                self.zeros += 1;
+                continue;
+            }
+            for byte in self.value.iter_mut() {
+                carry += *byte as u32 * self.alphabet.radix();
+                *byte = carry as u8;
+                carry >>= 8;
+            }
+            while carry > 0 {
+                self.value.push(carry as u8);
+                carry >>= 8;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<R: Read> Iterator for Reader<R> {
+    type Item = Result<u8, InError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.decoded.is_none() {
+            if let Err(e) = self.decode() {
+                return Some(Err(e));
+            }
+            let mut bytes = vec![0u8; self.zeros];
+            bytes.extend(self.value.iter().rev());
+            self.decoded = Some(bytes.into_iter());
+        }
+        Some(Ok(self.decoded.as_mut()?.next()?))
+    }
+}
+
+impl<R: Read> ByteReader for Reader<R> {
+    fn location(&self) -> Location {
+        self.in_bytes.location()
+    }
+}
+
+/// Writes bytes to the output stream as a single number written with the digits of the provided
+/// [Alphabet]
+///
+/// The byte stream is read as a big endian number; every leading zero byte is written as a leading
+/// zero digit. Since the whole stream is needed to compute the first digit, bytes are buffered
+/// and only written on [finish].
+///
+/// [finish]: crate::byte_writer::ByteWriter::finish
+pub struct Writer<W: Write> {
+    out_bytes: W,
+    alphabet: Alphabet,
+    bytes: Vec<u8>,
+}
+
+impl<W: Write> Writer<W> {
+    pub fn new(out_bytes: W, alphabet: Alphabet) -> Self {
+        Writer {
+            out_bytes,
+            alphabet,
+            bytes: vec![],
+        }
+    }
+}
+
+impl<W: Write> ByteWriter for Writer<W> {
+    fn write(&mut self, byte: u8) -> Result<(), OutError> {
+        self.bytes.push(byte);
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), OutError> {
+        let zeros = self.bytes.iter().take_while(|&&b| b == 0).count();
+        let mut digits = vec![]; // little endian
+        for byte in &self.bytes[zeros..] {
+            let mut carry = *byte as u32;
+            for digit in digits.iter_mut() {
+                carry += *digit << 8;
+                *digit = carry % self.alphabet.radix();
+                carry /= self.alphabet.radix();
+            }
+            while carry > 0 {
+                digits.push(carry % self.alphabet.radix());
+                carry /= self.alphabet.radix();
+            }
+        }
+        let mut string = vec![self.alphabet.symbols[0]; zeros];
+        string.extend(
+            digits
+                .iter()
+                .rev()
+                .map(|&d| self.alphabet.symbols[d as usize]),
+        );
+        self.bytes.clear();
+        util::write(&mut self.out_bytes, string.as_slice(), string.len())?;
+        util::flush(&mut self.out_bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(input: &[u8], alphabet: Alphabet) -> Vec<u8> {
+        let mut output = vec![];
+        let mut writer = Writer::new(&mut output, alphabet);
+        for b in input {
+            writer.write(*b).unwrap();
+        }
+        writer.finish().unwrap();
+        output
+    }
+
+    fn decode(input: &[u8], alphabet: Alphabet) -> Vec<u8> {
+        Reader::new(input, alphabet).map(|b| b.unwrap()).collect()
+    }
+
+    #[test]
+    fn alphabet() {
+        assert_eq!(58, Alphabet::base58().radix());
+        assert_eq!(58, Alphabet::base58_flickr().radix());
+        assert_eq!(62, Alphabet::base62().radix());
+        assert!(Alphabet::new("01").is_some());
+        assert!(Alphabet::new("0").is_none());
+        assert!(Alphabet::new("010").is_none());
+        assert!(Alphabet::new("0 1").is_none());
+    }
+
+    #[test]
+    fn read() {
+        assert_eq!(
+            b"Hello World!".as_slice(),
+            decode(b"2NEpo7TZRRrLZSi2U", Alphabet::base58())
+        );
+        assert_eq!(
+            [0u8, 0, 0x28, 0x7f, 0xb4, 0xcd].as_slice(),
+            decode(b"11233QC4", Alphabet::base58())
+        );
+        assert_eq!([0u8].as_slice(), decode(b"1", Alphabet::base58()));
+        assert_eq!(b"".as_slice(), decode(b"", Alphabet::base58()));
+        assert_eq!(
+            [1u8, 0].as_slice(),
+            decode(b"1 0000\n0000", Alphabet::new("01").unwrap())
+        );
+    }
+
+    #[test]
+    fn read_invalid() {
+        let mut reader = Reader::new(b"Zi0Ca".as_slice(), Alphabet::base58());
+        assert!(matches!(
+            reader.next(),
+            Some(Err(InError::InvalidByte('0')))
+        ));
+        let output: Vec<u8> = reader.map(|b| b.unwrap()).collect();
+        assert_eq!(b"abc".as_slice(), output);
+    }
+
+    #[test]
+    fn write() {
+        assert_eq!(
+            b"2NEpo7TZRRrLZSi2U".as_slice(),
+            encode(b"Hello World!", Alphabet::base58())
+        );
+        assert_eq!(
+            b"11233QC4".as_slice(),
+            encode(&[0, 0, 0x28, 0x7f, 0xb4, 0xcd], Alphabet::base58())
+        );
+        assert_eq!(
+            b"11233pc4".as_slice(),
+            encode(&[0, 0, 0x28, 0x7f, 0xb4, 0xcd], Alphabet::base58_flickr())
+        );
+        assert_eq!(b"4".as_slice(), encode(&[4], Alphabet::base62()));
+        assert_eq!(
+            b"18OWF".as_slice(),
+            encode(&[0xff, 0xff, 0xff], Alphabet::base62())
+        );
+        assert_eq!(b"".as_slice(), encode(b"", Alphabet::base62()));
+    }
+}
@@ -1,7 +1,8 @@
+use crate::byte_reader::ByteReader;
 use crate::byte_writer::ByteWriter;
-use crate::error::{InError, OutError};
+use crate::error::{InError, Location, OutError};
 use crate::util;
-use std::io::{Bytes, Read, Write};
+use std::io::{Read, Write};
 
 /// An iterator over Result<u8,[InError]>
 ///
@@ -9,13 +10,13 @@ use std::io::{Bytes, Read, Write};
 ///
 /// [InError]: crate::error::InError
 pub struct Reader<R: Read> {
-    in_bytes: Bytes<R>,
+    in_bytes: util::Input<R>,
 }
 
 impl<R: Read> Reader<R> {
     pub fn new(read: R) -> Self {
         Reader {
-            in_bytes: read.bytes(),
+            in_bytes: util::Input::new(read),
         }
     }
 }
@@ -30,6 +31,12 @@ impl<R: Read> Iterator for Reader<R> {
     }
 }
 
+impl<R: Read> ByteReader for Reader<R> {
+    fn location(&self) -> Location {
+        self.in_bytes.location()
+    }
+}
+
 /// Writes raw bytes to the output stream
 pub struct Writer<W: Write> {
     out_bytes: W,
@@ -45,6 +52,10 @@ impl<W: Write> ByteWriter for Writer<W> {
     fn write(&mut self, byte: u8) -> Result<(), OutError> {
         util::write(&mut self.out_bytes, &[byte], 1)
     }
+
+    fn finish(&mut self) -> Result<(), OutError> {
+        util::flush(&mut self.out_bytes)
+    }
 }
 
 #[cfg(test)]
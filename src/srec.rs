@@ -0,0 +1,354 @@
+use crate::byte_reader::ByteReader;
+use crate::byte_writer::ByteWriter;
+use crate::error::{InError, Location, OutError};
+use crate::hexadecimal;
+use crate::image::{self, Image};
+use crate::util;
+use std::io::{Read, Write};
+
+/// S-record address width
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum AddressWidth {
+    /// S19: 16 bits addresses, S1 data records and S9 termination
+    S19,
+    /// S28: 24 bits addresses, S2 data records and S8 termination
+    S28,
+    /// S37: 32 bits addresses, S3 data records and S7 termination
+    S37,
+}
+
+impl AddressWidth {
+    /// Number of address bytes
+    fn bytes(self) -> usize {
+        match self {
+            AddressWidth::S19 => 2,
+            AddressWidth::S28 => 3,
+            AddressWidth::S37 => 4,
+        }
+    }
+    fn data_type(self) -> u8 {
+        match self {
+            AddressWidth::S19 => 1,
+            AddressWidth::S28 => 2,
+            AddressWidth::S37 => 3,
+        }
+    }
+    fn termination_type(self) -> u8 {
+        10 - self.data_type()
+    }
+}
+
+/// Number of address bytes of the provided record type
+fn address_bytes(record_type: u8) -> Option<usize> {
+    match record_type {
+        0 | 1 | 5 | 9 => Some(2),
+        2 | 6 | 8 => Some(3),
+        3 | 7 => Some(4),
+        _ => None,
+    }
+}
+
+/// An iterator over Result<u8,[InError]>
+///
+/// Reads the memory image coded by a Motorola S-record file, with any [AddressWidth]: every line
+/// is a record ('S', record type, byte count, address, data and checksum, in hexadecimal) whose
+/// checksum is validated. S1, S2 and S3 data records are placed at their address; the S0 header
+/// is ignored, S5 and S6 counts must match the number of data records read so far and S7, S8 or
+/// S9 termination records end the input. Empty lines are skipped.
+///
+/// The image begins at the lowest address; gaps between data records are filled with the provided
+/// fill byte (0xff by default, as erased flash memory), while overlapping records are invalid.
+/// Since records can come in any order, the whole input is read before the first byte is
+/// returned.
+///
+/// [InError]: crate::error::InError
+pub struct Reader<R: Read> {
+    in_bytes: util::Input<R>,
+    fill: u8,
+    line_number: usize,
+    records: usize,
+    image: Option<Image>,
+    bytes: Option<image::Bytes>,
+}
+
+impl<R: Read> Reader<R> {
+    pub fn new(read: R) -> Self {
+        Self::with_fill(read, 0xff)
+    }
+
+    /// Builds a reader filling gaps between data records with the provided byte
+    pub fn with_fill(read: R, fill: u8) -> Self {
+        Reader {
+            in_bytes: util::Input::new(read),
+            fill,
+            line_number: 0,
+            records: 0,
+            image: Some(Image::new()),
+            bytes: None,
+        }
+    }
+
+    /// Reads next record into self.image; returns false at the end of file
+    fn read_record(&mut self) -> Result<bool, InError> {
+        let line = match self.in_bytes.line() {
+            Some(line) => line.map_err(InError::StdIO)?,
+            None => return Ok(false),
+        };
+        self.line_number += 1;
+        let invalid = InError::InvalidLine(self.line_number);
+        let line = line.trim_ascii();
+        if line.is_empty() {
+            return Ok(true);
+        }
+        let (record_type, address_bytes) = match line {
+            [b'S', t, ..] => match address_bytes(t.wrapping_sub(b'0')) {
+                Some(address_bytes) => (t - b'0', address_bytes),
+                None => return Err(invalid),
+            },
+            _ => return Err(invalid),
+        };
+        let Some(record) = hexadecimal::decode(&line[2..]) else {
+            return Err(invalid);
+        };
+        if record.len() < 2 + address_bytes || record.len() != record[0] as usize + 1 {
+            return Err(invalid);
+        }
+        if record.iter().fold(0u8, |sum, &b| sum.wrapping_add(b)) != 0xff {
+            return Err(InError::InvalidChecksum(self.line_number));
+        }
+        let address = record[1..1 + address_bytes]
+            .iter()
+            .fold(0u64, |a, &b| (a << 8) | b as u64);
+        let data = &record[1 + address_bytes..record.len() - 1];
+        match record_type {
+            0 => {}
+            1..=3 => {
+                let image = self.image.as_mut().unwrap();
+                if !image.insert(address, data) {
+                    return Err(InError::InvalidAddress(self.line_number));
+                }
+                self.records += 1;
+            }
+            5 | 6 if data.is_empty() && address == self.records as u64 => {}
+            7..=9 if data.is_empty() => return Ok(false),
+            _ => return Err(invalid),
+        }
+        Ok(true)
+    }
+}
+
+impl<R: Read> Iterator for Reader<R> {
+    type Item = Result<u8, InError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.image.is_some() {
+            match self.read_record() {
+                Ok(true) => {}
+                Ok(false) => {
+                    let image = self.image.take().unwrap();
+                    self.bytes = Some(image.into_bytes(self.fill));
+                }
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        Some(Ok(self.bytes.as_mut()?.next()?))
+    }
+}
+
+impl<R: Read> ByteReader for Reader<R> {
+    fn location(&self) -> Location {
+        self.in_bytes.location()
+    }
+}
+
+/// Writes bytes to the output stream as a Motorola S-record file with the provided
+/// [AddressWidth]
+///
+/// An empty S0 header is followed by data records of the provided length (16 by default),
+/// starting from the provided base address (0 by default). The last partial record, the S5 (or
+/// S6) count and the termination record (with address 0) are only written on [finish].
+///
+/// [finish]: crate::byte_writer::ByteWriter::finish
+pub struct Writer<W: Write> {
+    out_bytes: W,
+    width: AddressWidth,
+    record_length: usize,
+    address: u64,
+    line_number: usize,
+    records: usize,
+    record: Vec<u8>,
+}
+
+impl<W: Write> Writer<W> {
+    pub fn new(out_bytes: W, width: AddressWidth) -> Self {
+        Self::with_layout(out_bytes, width, 16, 0)
+    }
+
+    /// Builds a writer with the provided number of bytes per data record (up to 250) and address
+    /// of the first byte
+    pub fn with_layout(
+        out_bytes: W,
+        width: AddressWidth,
+        record_length: usize,
+        address: u32,
+    ) -> Self {
+        Writer {
+            out_bytes,
+            width,
+            record_length: record_length.clamp(1, 250),
+            address: address as u64,
+            line_number: 0,
+            records: 0,
+            record: vec![],
+        }
+    }
+
+    fn write_record(&mut self, record_type: u8, address: u64, data: &[u8]) -> Result<(), OutError> {
+        let address_bytes = address_bytes(record_type).unwrap();
+        let mut record = vec![(address_bytes + data.len() + 1) as u8];
+        record.extend(&address.to_be_bytes()[8 - address_bytes..]);
+        record.extend(data);
+        let sum = record.iter().fold(0u8, |sum, &b| sum.wrapping_add(b));
+        record.push(!sum);
+        let mut string = format!("S{record_type}");
+        for b in record {
+            string.push_str(&format!("{b:02X}"));
+        }
+        string.push('\n');
+        self.line_number += 1;
+        util::write(&mut self.out_bytes, string.as_bytes(), string.len())
+    }
+
+    fn write_data(&mut self) -> Result<(), OutError> {
+        if self.line_number == 0 {
+            self.write_record(0, 0, &[])?;
+        }
+        if self.record.is_empty() {
+            return Ok(());
+        }
+        let end = self.address + self.record.len() as u64;
+        if end > 1 << (8 * self.width.bytes()) {
+            return Err(OutError::InvalidAddress(self.line_number + 1));
+        }
+        let record = std::mem::take(&mut self.record);
+        self.write_record(self.width.data_type(), self.address, &record)?;
+        self.address = end;
+        self.records += 1;
+        Ok(())
+    }
+}
+
+impl<W: Write> ByteWriter for Writer<W> {
+    fn write(&mut self, byte: u8) -> Result<(), OutError> {
+        self.record.push(byte);
+        if self.record.len() == self.record_length {
+            self.write_data()
+        } else {
+            Ok(())
+        }
+    }
+
+    fn finish(&mut self) -> Result<(), OutError> {
+        self.write_data()?;
+        if self.records > 0xffff {
+            self.write_record(6, self.records as u64, &[])?;
+        } else {
+            self.write_record(5, self.records as u64, &[])?;
+        }
+        self.write_record(self.width.termination_type(), 0, &[])?;
+        util::flush(&mut self.out_bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(input: &[u8], width: AddressWidth, address: u32) -> String {
+        let mut output = vec![];
+        let mut writer = Writer::with_layout(&mut output, width, 4, address);
+        for b in input {
+            writer.write(*b).unwrap();
+        }
+        writer.finish().unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    fn decode(input: &str) -> Vec<u8> {
+        Reader::new(input.as_bytes()).map(|b| b.unwrap()).collect()
+    }
+
+    #[test]
+    fn read() {
+        let input = "S00F000068656C6C6F202020202000003C\n\
+                     S11F00007C0802A6900100049421FFF07C6C1B787C8C23783C6000003863000026\n\
+                     S11F001C4BFFFFE5398000007D83637880010014382100107C0803A64E800020E9\n\
+                     S111003848656C6C6F20776F726C642E0A0042\n\
+                     S5030003F9\n\
+                     S9030000FC\n";
+        let output = decode(input);
+        assert_eq!(0x46, output.len());
+        assert_eq!([0x7c, 0x08, 0x02, 0xa6].as_slice(), &output[..4]);
+        assert_eq!(b"Hello world.\n\0".as_slice(), &output[0x38..]);
+    }
+
+    #[test]
+    fn read_address() {
+        let input =
+            "S20801000041424344EC\r\nS3060002000045B2\r\n\r\nS70500000000FA\r\nS1040000AA51\r\n";
+        let reader = Reader::with_fill(input.as_bytes(), 0);
+        let output: Vec<u8> = reader.map(|b| b.unwrap()).collect();
+        assert_eq!(0x10001, output.len());
+        assert_eq!(b"ABCD\0".as_slice(), &output[..5]);
+        assert_eq!(0x45, output[0x10000]);
+    }
+
+    #[test]
+    fn read_invalid() {
+        let input =
+            "S1040000AA51\nS1040000AA52\nS1040000BB40\nS104000xBB40\nS4030000FC\nS5030002FA\n";
+        let mut reader = Reader::new(input.as_bytes());
+        assert!(matches!(
+            reader.next(),
+            Some(Err(InError::InvalidChecksum(2)))
+        ));
+        assert!(matches!(
+            reader.next(),
+            Some(Err(InError::InvalidAddress(3)))
+        ));
+        assert!(matches!(reader.next(), Some(Err(InError::InvalidLine(4)))));
+        assert!(matches!(reader.next(), Some(Err(InError::InvalidLine(5)))));
+        assert!(matches!(reader.next(), Some(Err(InError::InvalidLine(6)))));
+        assert_eq!(0xaa, reader.next().unwrap().unwrap());
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn write() {
+        assert_eq!(
+            "S0030000FC\nS107000041424344EE\nS104000445B2\nS5030002FA\nS9030000FC\n",
+            encode(b"ABCDE", AddressWidth::S19, 0)
+        );
+        assert_eq!(
+            "S0030000FC\nS20501000045B4\nS5030001FB\nS804000000FB\n",
+            encode(b"E", AddressWidth::S28, 0x10000)
+        );
+        assert_eq!(
+            "S0030000FC\nS5030000FC\nS70500000000FA\n",
+            encode(b"", AddressWidth::S37, 0)
+        );
+        let mut output = vec![];
+        let mut writer = Writer::with_layout(&mut output, AddressWidth::S19, 4, 0xfffe);
+        for b in b"ABC" {
+            writer.write(*b).unwrap();
+        }
+        assert!(matches!(writer.finish(), Err(OutError::InvalidAddress(2))));
+    }
+
+    #[test]
+    fn roundtrip() {
+        let input: Vec<u8> = (0..=255).collect();
+        for width in [AddressWidth::S19, AddressWidth::S28, AddressWidth::S37] {
+            assert_eq!(input, decode(&encode(&input, width, 0x1234)));
+        }
+    }
+}
@@ -0,0 +1,472 @@
+use crate::error::{InError, Location, OutError};
+use crate::util::{self, Input};
+use std::collections::VecDeque;
+use std::io::{Read, Write};
+
+/// Output format of the writers coding every byte as a string of digits (f.e. [hexadecimal])
+///
+/// Default format writes digits with no separators at all, in lower case.
+///
+/// # Examples
+///
+/// MAC address style hexadecimal output
+/// ```
+/// use bread_cli::*;
+/// use bread_cli::byte_writer::ByteWriter;
+///
+/// let format = text::Format {
+///     separator: ":".to_string(),
+///     uppercase: true,
+///     ..Default::default()
+/// };
+/// let mut output = vec![];
+/// let mut writer = hexadecimal::Writer::with_format(&mut output, format);
+/// for byte in [0xde, 0xad, 0xbe, 0xef] {
+///     writer.write(byte).unwrap();
+/// }
+/// writer.finish().unwrap();
+/// assert_eq!(b"DE:AD:BE:EF".as_slice(), output);
+/// ```
+///
+/// [hexadecimal]: crate::hexadecimal
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct Format {
+    /// written between bytes, except at line breaks (f.e. ' ', ',' or ':')
+    pub separator: String,
+    /// written before every byte (f.e. '0x', '0b' or '\x')
+    pub prefix: String,
+    /// upper case digits
+    pub uppercase: bool,
+    /// number of bytes per line, no line breaks if 0
+    pub wrap: usize,
+    /// newline written at the end of the output (unless empty)
+    pub newline: bool,
+}
+
+impl Format {
+    /// Same format, with a single space as separator if empty (for writers of values which would
+    /// otherwise be run together, f.e. [integer])
+    ///
+    /// [integer]: crate::integer
+    pub(crate) fn with_default_separator(self) -> Self {
+        if self.separator.is_empty() {
+            Format {
+                separator: " ".to_string(),
+                ..self
+            }
+        } else {
+            self
+        }
+    }
+}
+
+/// Writes digit strings to the output stream according to a [Format]
+pub(crate) struct Formatter {
+    format: Format,
+    count: usize,
+}
+
+impl Formatter {
+    pub(crate) fn new(format: Format) -> Self {
+        Formatter { format, count: 0 }
+    }
+
+    pub(crate) fn write<W: Write>(
+        &mut self,
+        out_bytes: &mut W,
+        digits: &[u8],
+    ) -> Result<(), OutError> {
+        let mut string = Vec::with_capacity(digits.len());
+        if self.count > 0 {
+            if self.format.wrap > 0 && self.count.is_multiple_of(self.format.wrap) {
+                string.push(b'\n');
+            } else {
+                string.extend(self.format.separator.as_bytes());
+            }
+        }
+        string.extend(self.format.prefix.as_bytes());
+        if self.format.uppercase {
+            string.extend(digits.iter().map(|d| d.to_ascii_uppercase()));
+        } else {
+            string.extend(digits);
+        }
+        self.count += 1;
+        util::write(out_bytes, string.as_slice(), string.len())
+    }
+
+    pub(crate) fn finish<W: Write>(&mut self, out_bytes: &mut W) -> Result<(), OutError> {
+        if self.format.newline && self.count > 0 {
+            util::write(out_bytes, b"\n", 1)?;
+        }
+        util::flush(out_bytes)
+    }
+}
+
+/// Input syntax of the readers parsing every byte as a string of digits (f.e. [hexadecimal]), in
+/// lenient mode
+///
+/// In lenient mode input is split in tokens by whitespace, separator characters and, optionally,
+/// brackets ('[', ']', '{', '}', '(' and ')'); every token may begin with one of the prefixes and
+/// may code more bytes, or a single byte with less digits than needed (f.e. '0x1' is read as
+/// 0x01).
+///
+/// # Examples
+///
+/// ```
+/// use bread_cli::*;
+///
+/// let input = b"{0xde, 0xad, 0x1}";
+/// let reader = hexadecimal::Reader::lenient(input.as_slice(), text::Lenient::hexadecimal());
+/// let output: Vec<u8> = reader.map(|b| b.unwrap()).collect();
+/// assert_eq!([0xde, 0xad, 0x01].as_slice(), output);
+/// ```
+///
+/// [hexadecimal]: crate::hexadecimal
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Lenient {
+    /// allowed at the beginning of every token (f.e. '0x')
+    pub prefixes: Vec<String>,
+    /// characters allowed between tokens, in addition to whitespace (f.e. ',')
+    pub separators: String,
+    /// brackets allowed between tokens
+    pub brackets: bool,
+}
+
+impl Default for Lenient {
+    /// No prefixes, ',', ':' and ';' separators, brackets allowed
+    fn default() -> Self {
+        Lenient {
+            prefixes: vec![],
+            separators: ",:;".to_string(),
+            brackets: true,
+        }
+    }
+}
+
+impl Lenient {
+    /// Default syntax plus '0x', '0X' and '\x' prefixes
+    pub fn hexadecimal() -> Self {
+        Lenient {
+            prefixes: vec!["0x".to_string(), "0X".to_string(), "\\x".to_string()],
+            ..Default::default()
+        }
+    }
+    /// Default syntax plus '0b' and '0B' prefixes
+    pub fn binary() -> Self {
+        Lenient {
+            prefixes: vec!["0b".to_string(), "0B".to_string()],
+            ..Default::default()
+        }
+    }
+    fn separator(&self, c: u8) -> bool {
+        self.separators.as_bytes().contains(&c) || (self.brackets && b"[]{}()".contains(&c))
+    }
+}
+
+enum Symbol {
+    Digit(u8),
+    /// end of a token with the provided number of digits
+    Boundary(usize),
+}
+
+/// Reads bytes coded as strings of digits from the input stream: whitespace is skipped and, if
+/// [Lenient], so are separators and prefixes
+pub(crate) struct Scanner<R: Read> {
+    in_bytes: Input<R>,
+    lenient: Option<Lenient>,
+    lookahead: VecDeque<(u8, Location)>,
+    location: Location,
+    token: usize,
+}
+
+impl<R: Read> Scanner<R> {
+    pub(crate) fn new(read: R, lenient: Option<Lenient>) -> Self {
+        Scanner {
+            in_bytes: Input::new(read),
+            lenient,
+            lookahead: VecDeque::new(),
+            location: Location::default(),
+            token: 0,
+        }
+    }
+
+    /// Location of the last character read
+    pub(crate) fn location(&self) -> Location {
+        self.location
+    }
+
+    fn next_char(&mut self) -> Option<std::io::Result<u8>> {
+        match self.lookahead.pop_front() {
+            Some((c, location)) => {
+                self.location = location;
+                Some(Ok(c))
+            }
+            None => {
+                let c = self.in_bytes.next();
+                self.location = self.in_bytes.location();
+                c
+            }
+        }
+    }
+
+    /// Skips the longest prefix the upcoming characters begin with, if any
+    fn skip_prefix(&mut self) -> std::io::Result<()> {
+        let Some(lenient) = &self.lenient else {
+            return Ok(());
+        };
+        let max = lenient.prefixes.iter().map(|p| p.len()).max().unwrap_or(0);
+        while self.lookahead.len() < max {
+            match self.in_bytes.next() {
+                Some(c) => self.lookahead.push_back((c?, self.in_bytes.location())),
+                None => break,
+            }
+        }
+        let len = lenient
+            .prefixes
+            .iter()
+            .map(|p| p.as_bytes())
+            .filter(|p| {
+                let lookahead = self.lookahead.iter().map(|(c, _)| c);
+                lookahead.take(p.len()).eq(p.iter())
+            })
+            .map(|p| p.len())
+            .max()
+            .unwrap_or(0);
+        if let Some((_, location)) = self.lookahead.drain(..len).next_back() {
+            self.location = location;
+        }
+        Ok(())
+    }
+
+    fn next_symbol(&mut self) -> Option<std::io::Result<Symbol>> {
+        loop {
+            if self.token == 0 {
+                if let Err(e) = self.skip_prefix() {
+                    return Some(Err(e));
+                }
+            }
+            let c = match self.next_char() {
+                Some(Ok(c)) => c,
+                Some(Err(e)) => return Some(Err(e)),
+                None if self.token > 0 && self.lenient.is_some() => {
+                    return Some(Ok(Symbol::Boundary(std::mem::take(&mut self.token))));
+                }
+                None => return None,
+            };
+            let separator =
+                c.is_ascii_whitespace() || self.lenient.as_ref().is_some_and(|l| l.separator(c));
+            if !separator {
+                self.token += 1;
+                return Some(Ok(Symbol::Digit(c)));
+            }
+            if self.token > 0 && self.lenient.is_some() {
+                return Some(Ok(Symbol::Boundary(std::mem::take(&mut self.token))));
+            }
+        }
+    }
+
+    /// Reads next byte, coded by the provided number of digits in the provided radix
+    pub(crate) fn next_byte<F>(
+        &mut self,
+        digits: usize,
+        radix: u32,
+        valid: F,
+    ) -> Option<Result<u8, InError>>
+    where
+        F: Fn(char) -> Option<u8>,
+    {
+        let mut value = 0u32;
+        let mut count = 0usize;
+        let mut last = '0';
+        while count < digits {
+            match self.next_symbol() {
+                None if count == 0 => return None,
+                None => {
+                    return Some(Err(InError::ShortIO {
+                        bytes: count,
+                        expected: digits,
+                    }))
+                }
+                Some(Err(e)) => return Some(Err(InError::StdIO(e))),
+                Some(Ok(Symbol::Boundary(len))) => {
+                    if count == 0 {
+                        continue;
+                    } else if len == count {
+                        break;
+                    } else {
+                        return Some(Err(InError::ShortIO {
+                            bytes: count,
+                            expected: digits,
+                        }));
+                    }
+                }
+                Some(Ok(Symbol::Digit(c))) => {
+                    last = c as char;
+                    match valid(last) {
+                        Some(digit) => value = value * radix + digit as u32,
+                        None => return Some(Err(InError::InvalidByte(last))),
+                    }
+                    count += 1;
+                }
+            }
+        }
+        if value > u8::MAX as u32 {
+            Some(Err(InError::InvalidByte(last)))
+        } else {
+            Some(Ok(value as u8))
+        }
+    }
+}
+
+/// Reads bytes coded by a list of values, one per token of the input stream (see [Input::token]),
+/// such as [integer] values
+///
+/// [integer]: crate::integer
+pub(crate) struct Tokens<R: Read> {
+    in_bytes: Input<R>,
+    bytes: Vec<u8>,
+    consumed: usize,
+}
+
+impl<R: Read> Tokens<R> {
+    pub(crate) fn new(read: R) -> Self {
+        Tokens {
+            in_bytes: Input::new(read),
+            bytes: vec![],
+            consumed: 0,
+        }
+    }
+
+    /// Location of the last character read
+    pub(crate) fn location(&self) -> Location {
+        self.in_bytes.location()
+    }
+
+    /// Reads next byte; once all the bytes of the previous token are read, next token is parsed
+    /// by the provided function into the bytes coding its value
+    pub(crate) fn next_byte<F>(&mut self, parse: F) -> Option<Result<u8, InError>>
+    where
+        F: FnOnce(&[u8]) -> Result<Vec<u8>, InError>,
+    {
+        if self.consumed == self.bytes.len() {
+            let token = match self.in_bytes.token()? {
+                Ok(token) => token,
+                Err(e) => return Some(Err(InError::StdIO(e))),
+            };
+            match parse(&token) {
+                Ok(bytes) => self.bytes = bytes,
+                Err(e) => return Some(Err(e)),
+            }
+            self.consumed = 0;
+        }
+        self.consumed += 1;
+        Some(Ok(self.bytes[self.consumed - 1]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(format: Format, tokens: &[&str]) -> String {
+        let mut output = vec![];
+        let mut formatter = Formatter::new(format);
+        for token in tokens {
+            formatter.write(&mut output, token.as_bytes()).unwrap();
+        }
+        formatter.finish(&mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    fn scan(input: &str, lenient: Option<Lenient>) -> Vec<Result<u8, InError>> {
+        let mut scanner = Scanner::new(input.as_bytes(), lenient);
+        std::iter::from_fn(|| scanner.next_byte(2, 16, |c| c.to_digit(16).map(|d| d as u8)))
+            .collect()
+    }
+
+    #[test]
+    fn scan_strict() {
+        let bytes = scan("a4 1\nb", None);
+        assert!(matches!(bytes[..], [Ok(0xa4), Ok(0x1b)]));
+        let bytes = scan("a4,1b", None);
+        assert!(matches!(
+            bytes[..],
+            [Ok(0xa4), Err(InError::InvalidByte(',')), Ok(0x1b)]
+        ));
+        let bytes = scan("0xa4", None);
+        assert!(matches!(bytes[0], Err(InError::InvalidByte('x'))));
+    }
+
+    #[test]
+    fn scan_lenient() {
+        let bytes = scan("[0xa4, 0x1b0c,\\x1;de:ad]", Some(Lenient::hexadecimal()));
+        assert!(matches!(
+            bytes[..],
+            [Ok(0xa4), Ok(0x1b), Ok(0x0c), Ok(0x01), Ok(0xde), Ok(0xad)]
+        ));
+        let bytes = scan("0x1b0", Some(Lenient::hexadecimal()));
+        assert!(matches!(
+            bytes[..],
+            [
+                Ok(0x1b),
+                Err(InError::ShortIO {
+                    bytes: 1,
+                    expected: 2
+                })
+            ]
+        ));
+        let bytes = scan("a|b", Some(Lenient::hexadecimal()));
+        assert!(matches!(bytes[0], Err(InError::InvalidByte('|'))));
+    }
+
+    #[test]
+    fn scan_position() {
+        let mut scanner = Scanner::new("a4\n 1g".as_bytes(), None);
+        let valid = |c: char| c.to_digit(16).map(|d| d as u8);
+        assert!(matches!(scanner.next_byte(2, 16, valid), Some(Ok(0xa4))));
+        assert!(matches!(
+            scanner.next_byte(2, 16, valid),
+            Some(Err(InError::InvalidByte('g')))
+        ));
+        let expected = Location {
+            offset: 5,
+            line: 2,
+            column: 3,
+        };
+        assert_eq!(expected, scanner.location());
+        let mut scanner = Scanner::new("0xg1".as_bytes(), Some(Lenient::hexadecimal()));
+        assert!(scanner.next_byte(2, 16, valid).unwrap().is_err());
+        assert_eq!(2, scanner.location().offset);
+    }
+
+    #[test]
+    fn default() {
+        assert_eq!("a41b", format(Format::default(), &["a4", "1b"]));
+    }
+
+    #[test]
+    fn separator_prefix_uppercase() {
+        let f = Format {
+            separator: ", ".to_string(),
+            prefix: "0x".to_string(),
+            uppercase: true,
+            ..Default::default()
+        };
+        assert_eq!("0xA4, 0x1B", format(f, &["a4", "1b"]));
+    }
+
+    #[test]
+    fn wrap_newline() {
+        let f = Format {
+            separator: " ".to_string(),
+            wrap: 2,
+            newline: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            "00 01\n02 03\n04\n",
+            format(f.clone(), &["00", "01", "02", "03", "04"])
+        );
+        assert_eq!("", format(f, &[]));
+    }
+}
@@ -0,0 +1,393 @@
+use crate::byte_reader::ByteReader;
+use crate::byte_writer::ByteWriter;
+use crate::error::{InError, Location, OutError};
+use crate::integer::{Endianness, Integer};
+use crate::utf8::{Decoder, Invalid, Unit};
+use crate::util;
+use std::collections::VecDeque;
+use std::io::{Read, Write};
+
+/// Byte order mark, the code point U+FEFF at the beginning of text
+const BOM: u32 = 0xfeff;
+
+/// Unicode encoding form with code units wider than a byte
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Form {
+    /// 16 bits code units, code points beyond U+FFFF coded by surrogate pairs
+    Utf16,
+    /// 32 bits code units, one for every code point
+    Utf32,
+}
+
+impl Form {
+    /// Type of the code units
+    fn unit(self, endianness: Endianness) -> Integer {
+        let bits = match self {
+            Form::Utf16 => 16,
+            Form::Utf32 => 32,
+        };
+        Integer::new(bits, false, endianness).unwrap()
+    }
+}
+
+/// Byte of an invalid code unit reported by the [Reader] (the one telling it is a surrogate)
+fn invalid(unit: u32) -> InError {
+    InError::InvalidByte((unit >> 8) as u8 as char)
+}
+
+/// An iterator over Result<u8,[InError]>
+///
+/// Reads bytes from the input stream as UTF-16 or UTF-32 text (according to the provided [Form]),
+/// returning the bytes of the same text encoded as UTF-8.
+///
+/// A leading byte order mark is skipped; if no [Endianness] is provided, it is detected by the
+/// byte order mark, big endian if missing.
+/// Invalid text is reported as:
+/// - [ShortIO], if the input ends in the middle of a code unit or surrogate pair
+/// - [OutOfRange], if a UTF-32 code unit is beyond U+10FFFF
+/// - [InvalidByte] for unpaired surrogates (reporting their most significant byte)
+///
+/// [InError]: crate::error::InError
+/// [ShortIO]: crate::error::ErrorType::ShortIO
+/// [OutOfRange]: crate::error::ErrorType::OutOfRange
+/// [InvalidByte]: crate::error::ErrorType::InvalidByte
+pub struct Reader<R: Read> {
+    in_bytes: util::Input<R>,
+    form: Form,
+    endianness: Option<Endianness>,
+    started: bool,
+    pending: Option<u32>,
+    bytes: VecDeque<u8>,
+}
+
+impl<R: Read> Reader<R> {
+    pub fn new(read: R, form: Form, endianness: Option<Endianness>) -> Self {
+        Reader {
+            in_bytes: util::Input::new(read),
+            form,
+            endianness,
+            started: false,
+            pending: None,
+            bytes: VecDeque::new(),
+        }
+    }
+
+    /// Reads next code unit
+    fn read_unit(&mut self) -> Option<Result<u32, InError>> {
+        if let Some(unit) = self.pending.take() {
+            return Some(Ok(unit));
+        }
+        let expected = self.form.unit(Endianness::Big).bits() / 8;
+        let mut bytes = Vec::with_capacity(expected);
+        while bytes.len() < expected {
+            match self.in_bytes.next() {
+                Some(Ok(b)) => bytes.push(b),
+                Some(Err(e)) => return Some(Err(InError::StdIO(e))),
+                None if bytes.is_empty() => return None,
+                None => {
+                    return Some(Err(InError::ShortIO {
+                        bytes: bytes.len(),
+                        expected,
+                    }));
+                }
+            }
+        }
+        let form = self.form;
+        let endianness = *self.endianness.get_or_insert_with(|| {
+            if form.unit(Endianness::Little).encode(BOM as u64) == bytes {
+                Endianness::Little
+            } else {
+                Endianness::Big
+            }
+        });
+        Some(Ok(self.form.unit(endianness).decode(&bytes) as u32))
+    }
+
+    /// Reads next character
+    fn read_char(&mut self) -> Option<Result<char, InError>> {
+        let mut unit = match self.read_unit()? {
+            Ok(unit) => unit,
+            Err(e) => return Some(Err(e)),
+        };
+        if !self.started {
+            self.started = true;
+            if unit == BOM {
+                unit = match self.read_unit()? {
+                    Ok(unit) => unit,
+                    Err(e) => return Some(Err(e)),
+                };
+            }
+        }
+        if self.form == Form::Utf16 && (0xd800..0xdc00).contains(&unit) {
+            let low = match self.read_unit() {
+                Some(Ok(low)) => low,
+                Some(Err(InError::ShortIO { bytes, .. })) => {
+                    return Some(Err(InError::ShortIO {
+                        bytes: bytes + 2,
+                        expected: 4,
+                    }));
+                }
+                Some(Err(e)) => return Some(Err(e)),
+                None => {
+                    return Some(Err(InError::ShortIO {
+                        bytes: 2,
+                        expected: 4,
+                    }));
+                }
+            };
+            if !(0xdc00..0xe000).contains(&low) {
+                // decoded again as the next character
+                self.pending = Some(low);
+                return Some(Err(invalid(unit)));
+            }
+            unit = 0x10000 + ((unit - 0xd800) << 10) + (low - 0xdc00);
+        }
+        match char::from_u32(unit) {
+            Some(c) => Some(Ok(c)),
+            None if unit > char::MAX as u32 => Some(Err(InError::OutOfRange)),
+            None => Some(Err(invalid(unit))),
+        }
+    }
+}
+
+impl<R: Read> Iterator for Reader<R> {
+    type Item = Result<u8, InError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.bytes.is_empty() {
+            match self.read_char()? {
+                Ok(c) => self.bytes.extend(c.encode_utf8(&mut [0u8; 4]).as_bytes()),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        self.bytes.pop_front().map(Ok)
+    }
+}
+
+impl<R: Read> ByteReader for Reader<R> {
+    fn location(&self) -> Location {
+        self.in_bytes.location()
+    }
+}
+
+/// Writes bytes to the output stream, as UTF-8 text encoded to UTF-16 or UTF-32 (according to
+/// the provided [Form]) with the provided [Endianness]
+///
+/// A byte order mark is written before the text if requested. Invalid UTF-8 sequences are written
+/// according to the provided [Invalid] representation, as described for the [utf8] writer.
+///
+/// [utf8]: crate::utf8::Writer
+pub struct Writer<W: Write> {
+    out_bytes: W,
+    unit: Integer,
+    form: Form,
+    bom: bool,
+    invalid: Invalid,
+    decoder: Decoder,
+}
+
+impl<W: Write> Writer<W> {
+    pub fn new(out_bytes: W, form: Form, endianness: Endianness) -> Self {
+        Self::with_options(out_bytes, form, endianness, false, Invalid::default())
+    }
+
+    pub fn with_options(
+        out_bytes: W,
+        form: Form,
+        endianness: Endianness,
+        bom: bool,
+        invalid: Invalid,
+    ) -> Self {
+        Writer {
+            out_bytes,
+            unit: form.unit(endianness),
+            form,
+            bom,
+            invalid,
+            decoder: Decoder::new(),
+        }
+    }
+
+    fn write_char(&mut self, c: char) -> Result<(), OutError> {
+        if self.bom {
+            self.bom = false;
+            self.write_char(char::from_u32(BOM).unwrap())?;
+        }
+        let mut bytes = vec![];
+        match self.form {
+            Form::Utf16 => {
+                for unit in c.encode_utf16(&mut [0u16; 2]) {
+                    bytes.extend(self.unit.encode(*unit as u64));
+                }
+            }
+            Form::Utf32 => bytes.extend(self.unit.encode(c as u64)),
+        }
+        util::write(&mut self.out_bytes, &bytes, bytes.len())
+    }
+
+    /// Writes the units decoded so far
+    fn write_units(&mut self) -> Result<(), OutError> {
+        while let Some(unit) = self.decoder.next() {
+            match unit {
+                Unit::Char(c) => self.write_char(c)?,
+                Unit::Invalid(malformed, bytes) => match self.invalid {
+                    Invalid::Strict => return Err(malformed.error(|b| b)),
+                    Invalid::Replace => self.write_char(char::REPLACEMENT_CHARACTER)?,
+                    Invalid::Escape => {
+                        for b in bytes {
+                            for c in format!("\\x{b:02x}").chars() {
+                                self.write_char(c)?;
+                            }
+                        }
+                    }
+                },
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write> ByteWriter for Writer<W> {
+    fn write(&mut self, byte: u8) -> Result<(), OutError> {
+        self.decoder.push(byte);
+        self.write_units()
+    }
+
+    fn finish(&mut self) -> Result<(), OutError> {
+        self.decoder.end();
+        self.write_units()?;
+        util::flush(&mut self.out_bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(
+        input: &[u8],
+        form: Form,
+        endianness: Option<Endianness>,
+    ) -> Vec<Result<u8, InError>> {
+        Reader::new(input, form, endianness).collect()
+    }
+
+    fn encode(input: &str, form: Form, endianness: Endianness, bom: bool) -> Vec<u8> {
+        let mut output = vec![];
+        let mut writer = Writer::with_options(&mut output, form, endianness, bom, Invalid::Strict);
+        for b in input.as_bytes() {
+            writer.write(*b).unwrap();
+        }
+        writer.finish().unwrap();
+        output
+    }
+
+    fn text(bytes: Vec<Result<u8, InError>>) -> String {
+        String::from_utf8(bytes.into_iter().map(|b| b.unwrap()).collect()).unwrap()
+    }
+
+    #[test]
+    fn read() {
+        let input = [0x41, 0x00, 0xe9, 0x00, 0x3d, 0xd8, 0x00, 0xde];
+        let le = Some(Endianness::Little);
+        assert_eq!("Aé😀", text(decode(&input, Form::Utf16, le)));
+        let input = [0xfe, 0xff, 0x00, 0x41, 0x20, 0xac];
+        assert_eq!("A€", text(decode(&input, Form::Utf16, None)));
+        let input = [0xff, 0xfe, 0x41, 0x00];
+        assert_eq!("A", text(decode(&input, Form::Utf16, None)));
+        assert_eq!("A", text(decode(&input, Form::Utf16, le)));
+        let input = [0x00, 0x41];
+        assert_eq!("A", text(decode(&input, Form::Utf16, None)));
+        let input = [0xff, 0xfe, 0x00, 0x00, 0x00, 0xf6, 0x01, 0x00];
+        assert_eq!("😀", text(decode(&input, Form::Utf32, None)));
+        let input = [0x00, 0x00, 0x00, 0x41];
+        let be = Some(Endianness::Big);
+        assert_eq!("A", text(decode(&input, Form::Utf32, be)));
+    }
+
+    #[test]
+    fn read_invalid() {
+        let be = Some(Endianness::Big);
+        let bytes = decode(&[0xdc, 0x00, 0xd8, 0x00, 0x00, 0x41, 0xd8], Form::Utf16, be);
+        assert!(matches!(
+            bytes[..],
+            [
+                Err(InError::InvalidByte('\u{dc}')),
+                Err(InError::InvalidByte('\u{d8}')),
+                Ok(0x41),
+                Err(InError::ShortIO {
+                    bytes: 1,
+                    expected: 2
+                })
+            ]
+        ));
+        let bytes = decode(&[0xd8, 0x00], Form::Utf16, be);
+        assert!(matches!(
+            bytes[..],
+            [Err(InError::ShortIO {
+                bytes: 2,
+                expected: 4
+            })]
+        ));
+        let bytes = decode(
+            &[0x00, 0x11, 0x00, 0x00, 0x00, 0x00, 0xd8, 0x00],
+            Form::Utf32,
+            be,
+        );
+        assert!(matches!(
+            bytes[..],
+            [
+                Err(InError::OutOfRange),
+                Err(InError::InvalidByte('\u{d8}'))
+            ]
+        ));
+    }
+
+    #[test]
+    fn write() {
+        assert_eq!(
+            [0x41, 0x00, 0xe9, 0x00, 0x3d, 0xd8, 0x00, 0xde].as_slice(),
+            encode("Aé😀", Form::Utf16, Endianness::Little, false)
+        );
+        assert_eq!(
+            [0xfe, 0xff, 0x20, 0xac].as_slice(),
+            encode("€", Form::Utf16, Endianness::Big, true)
+        );
+        assert_eq!(
+            [0xff, 0xfe, 0x00, 0x00, 0x41, 0x00, 0x00, 0x00].as_slice(),
+            encode("A", Form::Utf32, Endianness::Little, true)
+        );
+        assert!(encode("", Form::Utf16, Endianness::Little, true).is_empty());
+    }
+
+    #[test]
+    fn write_invalid() {
+        let mut output = vec![];
+        let mut writer = Writer::new(&mut output, Form::Utf16, Endianness::Big);
+        assert!(matches!(
+            writer.write(0xff),
+            Err(OutError::InvalidByte(0xff))
+        ));
+        let mut output = vec![];
+        let mut writer = Writer::with_options(
+            &mut output,
+            Form::Utf16,
+            Endianness::Big,
+            false,
+            Invalid::Replace,
+        );
+        writer.write(0xc3).unwrap();
+        writer.finish().unwrap();
+        assert_eq!([0xff, 0xfd].as_slice(), output);
+    }
+
+    #[test]
+    fn roundtrip() {
+        let input = "aé€😀\n";
+        for form in [Form::Utf16, Form::Utf32] {
+            for endianness in [Endianness::Little, Endianness::Big] {
+                let bytes = encode(input, form, endianness, true);
+                assert_eq!(input, text(decode(&bytes, form, None)));
+            }
+        }
+    }
+}
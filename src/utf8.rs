@@ -0,0 +1,568 @@
+use crate::byte_reader::ByteReader;
+use crate::byte_writer::ByteWriter;
+use crate::error::{ErrorType, InError, Location, OutError};
+use crate::text::{Format, Formatter};
+use crate::util;
+use std::collections::VecDeque;
+use std::io::{Read, Write};
+
+/// Outcome of decoding the bytes of a UTF-8 sequence, starting from its lead byte
+enum Decoded {
+    /// valid sequence
+    Char(char),
+    /// valid so far, more bytes expected
+    Incomplete,
+    /// invalid sequence: the provided number of bytes belongs to it, the others are to be
+    /// decoded again
+    Malformed(Malformed, usize),
+}
+
+/// Kind of invalid UTF-8 sequence
+#[derive(Clone, Copy)]
+pub(crate) enum Malformed {
+    /// byte not allowed at its position
+    Byte(u8),
+    /// sequence interrupted after the provided number of bytes, out of the expected
+    Truncated(usize, usize),
+    /// code point coded by more bytes than needed
+    Overlong,
+    /// code point beyond U+10FFFF
+    OutOfRange,
+}
+
+impl Malformed {
+    pub(crate) fn error<Byte>(self, byte: impl Fn(u8) -> Byte) -> ErrorType<Byte> {
+        match self {
+            Malformed::Byte(b) => ErrorType::InvalidByte(byte(b)),
+            Malformed::Truncated(bytes, expected) => ErrorType::Truncated { bytes, expected },
+            Malformed::Overlong => ErrorType::Overlong,
+            Malformed::OutOfRange => ErrorType::OutOfRange,
+        }
+    }
+}
+
+/// Length of the UTF-8 sequence beginning with the provided lead byte
+fn length(lead: u8) -> usize {
+    match lead {
+        0xc0..=0xdf => 2,
+        0xe0..=0xef => 3,
+        0xf0..=0xf7 => 4,
+        _ => 1,
+    }
+}
+
+/// Decodes the UTF-8 sequence at the beginning of the provided bytes
+fn decode(bytes: &[u8]) -> Decoded {
+    let lead = bytes[0];
+    let (length, min, max) = match lead {
+        0x00..=0x7f => return Decoded::Char(lead as char),
+        0xc0 | 0xc1 => (2, 0x80, 0xbf),
+        0xc2..=0xdf => (2, 0x80, 0xbf),
+        0xe0 => (3, 0xa0, 0xbf),
+        0xed => (3, 0x80, 0x9f),
+        0xe1..=0xef => (3, 0x80, 0xbf),
+        0xf0 => (4, 0x90, 0xbf),
+        0xf4 => (4, 0x80, 0x8f),
+        0xf1..=0xf3 => (4, 0x80, 0xbf),
+        _ => return Decoded::Malformed(Malformed::Byte(lead), 1),
+    };
+    for (i, &b) in bytes.iter().enumerate().skip(1) {
+        if b & 0xc0 != 0x80 {
+            return Decoded::Malformed(Malformed::Truncated(i, length), i);
+        }
+        if i == 1 && (b < min || b > max) {
+            let malformed = match lead {
+                0xe0 | 0xf0 => Malformed::Overlong,
+                0xed => Malformed::Byte(b),
+                _ => Malformed::OutOfRange,
+            };
+            return Decoded::Malformed(malformed, 2);
+        }
+    }
+    if lead < 0xc2 && bytes.len() > 1 {
+        return Decoded::Malformed(Malformed::Overlong, 2);
+    }
+    if bytes.len() < length {
+        return Decoded::Incomplete;
+    }
+    match std::str::from_utf8(&bytes[..length]) {
+        Ok(s) => Decoded::Char(s.chars().next().unwrap()),
+        Err(_) => unreachable!("sequence already validated"),
+    }
+}
+
+/// Outcome of decoding UTF-8 bytes with a [Decoder]
+pub(crate) enum Unit {
+    /// valid sequence
+    Char(char),
+    /// invalid sequence, with its bytes
+    Invalid(Malformed, Vec<u8>),
+}
+
+/// Decoder of UTF-8 bytes provided one at a time, as written to text writers
+///
+/// Decoded units are returned by iterating the decoder after every [push]: bytes following an
+/// invalid sequence are decoded again. A trailing incomplete sequence is only returned, as
+/// invalid, after [end].
+///
+/// [push]: Decoder::push
+/// [end]: Decoder::end
+pub(crate) struct Decoder {
+    sequence: Vec<u8>,
+    end: bool,
+}
+
+impl Decoder {
+    pub(crate) fn new() -> Self {
+        Decoder {
+            sequence: Vec::with_capacity(4),
+            end: false,
+        }
+    }
+
+    pub(crate) fn push(&mut self, byte: u8) {
+        self.sequence.push(byte);
+    }
+
+    /// Marks the end of the input
+    pub(crate) fn end(&mut self) {
+        self.end = true;
+    }
+}
+
+impl Iterator for Decoder {
+    type Item = Unit;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.sequence.is_empty() {
+            return None;
+        }
+        match decode(&self.sequence) {
+            Decoded::Char(c) => {
+                self.sequence.clear();
+                Some(Unit::Char(c))
+            }
+            Decoded::Incomplete if self.end => {
+                let bytes = self.sequence.len();
+                let expected = length(self.sequence[0]);
+                let sequence = std::mem::take(&mut self.sequence);
+                Some(Unit::Invalid(
+                    Malformed::Truncated(bytes, expected),
+                    sequence,
+                ))
+            }
+            Decoded::Incomplete => None,
+            Decoded::Malformed(malformed, length) => {
+                let sequence = self.sequence.drain(..length).collect();
+                Some(Unit::Invalid(malformed, sequence))
+            }
+        }
+    }
+}
+
+/// Representation of invalid UTF-8 sequences in UTF-8 output
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum Invalid {
+    /// invalid sequences are an error
+    #[default]
+    Strict,
+    /// every invalid sequence is written as the replacement character '�' (U+FFFD)
+    Replace,
+    /// every byte of invalid sequences is written as a '\xHH' escape sequence
+    Escape,
+}
+
+/// Text written by the [Writer] for every character
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Default)]
+pub enum Style {
+    /// the character itself, UTF-8 encoded
+    #[default]
+    Text,
+    /// the code point of the character (g.e. 'U+00E9'), space separated
+    CodePoints,
+}
+
+/// An iterator over Result<u8,[InError]>
+///
+/// With the default [Style], reads bytes from the input stream as UTF-8 text: bytes of valid
+/// sequences are returned unchanged, while invalid ones are reported as:
+/// - [Truncated], if a lead byte is not followed by enough continuation bytes ([ShortIO] if the
+///   input ends first)
+/// - [Overlong], if a code point is coded by more bytes than needed (g.e. 0xc0 0xaf)
+/// - [OutOfRange], if a code point is beyond U+10FFFF
+/// - [InvalidByte] for any other byte not allowed at its position (g.e. unexpected continuation
+///   bytes or surrogates)
+///
+/// With [CodePoints] style, reads a list of code points (g.e. 'U+00E9') split by whitespace,
+/// ',', ';' or brackets, returning the UTF-8 bytes coding each of them; '\xHH' escape sequences
+/// (as written by [Writer] for invalid sequences) are read as the byte they code.
+///
+/// [InError]: crate::error::InError
+/// [Truncated]: crate::error::ErrorType::Truncated
+/// [ShortIO]: crate::error::ErrorType::ShortIO
+/// [Overlong]: crate::error::ErrorType::Overlong
+/// [OutOfRange]: crate::error::ErrorType::OutOfRange
+/// [InvalidByte]: crate::error::ErrorType::InvalidByte
+/// [CodePoints]: Style::CodePoints
+pub struct Reader<R: Read> {
+    in_bytes: util::Input<R>,
+    style: Style,
+    pending: VecDeque<(u8, Location)>,
+    start: Location,
+    bytes: VecDeque<u8>,
+}
+
+impl<R: Read> Reader<R> {
+    pub fn new(read: R) -> Self {
+        Self::with_style(read, Style::default())
+    }
+
+    pub fn with_style(read: R, style: Style) -> Self {
+        Reader {
+            in_bytes: util::Input::new(read),
+            style,
+            pending: VecDeque::new(),
+            start: Location::default(),
+            bytes: VecDeque::new(),
+        }
+    }
+
+    /// Reads next byte, along with its location
+    fn next_byte(&mut self) -> Option<std::io::Result<(u8, Location)>> {
+        match self.pending.pop_front() {
+            Some(b) => Some(Ok(b)),
+            None => Some(self.in_bytes.next()?.map(|b| (b, self.in_bytes.location()))),
+        }
+    }
+
+    /// Reads next UTF-8 sequence into self.bytes; self.start is set to the location of its first
+    /// byte
+    fn read_text(&mut self) -> Option<Result<(), InError>> {
+        let mut sequence = vec![];
+        let mut locations = vec![];
+        loop {
+            match self.next_byte() {
+                Some(Ok((b, location))) => {
+                    if sequence.is_empty() {
+                        self.start = location;
+                    }
+                    sequence.push(b);
+                    locations.push(location);
+                }
+                Some(Err(e)) => return Some(Err(InError::StdIO(e))),
+                None if sequence.is_empty() => return None,
+                None => {
+                    return Some(Err(InError::ShortIO {
+                        bytes: sequence.len(),
+                        expected: length(sequence[0]),
+                    }));
+                }
+            }
+            match decode(&sequence) {
+                Decoded::Char(_) => {
+                    self.bytes.extend(sequence);
+                    return Some(Ok(()));
+                }
+                Decoded::Incomplete => {}
+                Decoded::Malformed(malformed, length) => {
+                    for (&b, &location) in sequence.iter().zip(&locations).skip(length).rev() {
+                        self.pending.push_front((b, location));
+                    }
+                    return Some(Err(malformed.error(|b| b as char)));
+                }
+            }
+        }
+    }
+
+    /// Reads next code point into self.bytes
+    fn read_code_point(&mut self) -> Option<Result<(), InError>> {
+        let token = match self.in_bytes.token()? {
+            Ok(token) => token,
+            Err(e) => return Some(Err(InError::StdIO(e))),
+        };
+        let hex = match token.get(..2) {
+            Some(b"\\x" | b"U+" | b"u+") => &token[2..],
+            _ => return Some(Err(InError::InvalidByte(token[0] as char))),
+        };
+        if let Some(&c) = hex.iter().find(|c| !c.is_ascii_hexdigit()) {
+            return Some(Err(InError::InvalidByte(c as char)));
+        }
+        let last = *token.last().unwrap() as char;
+        let digits = if token[0] == b'\\' { 2..=2 } else { 4..=6 };
+        if !digits.contains(&hex.len()) {
+            return Some(Err(InError::InvalidByte(last)));
+        }
+        let value = hex.iter().fold(0, |value, &d| {
+            (value << 4) | (d as char).to_digit(16).unwrap()
+        });
+        if token[0] == b'\\' {
+            self.bytes.push_back(value as u8);
+            return Some(Ok(()));
+        }
+        match char::from_u32(value) {
+            Some(c) => {
+                self.bytes.extend(c.encode_utf8(&mut [0u8; 4]).as_bytes());
+                Some(Ok(()))
+            }
+            None if value > char::MAX as u32 => Some(Err(InError::OutOfRange)),
+            // surrogate
+            None => Some(Err(InError::InvalidByte(last))),
+        }
+    }
+}
+
+impl<R: Read> Iterator for Reader<R> {
+    type Item = Result<u8, InError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.bytes.is_empty() {
+            let read = match self.style {
+                Style::Text => self.read_text()?,
+                Style::CodePoints => self.read_code_point()?,
+            };
+            if let Err(e) = read {
+                return Some(Err(e));
+            }
+        }
+        Some(Ok(self.bytes.pop_front().unwrap()))
+    }
+}
+
+impl<R: Read> ByteReader for Reader<R> {
+    /// With the default [Style], location of the first byte of the last sequence read
+    fn location(&self) -> Location {
+        match self.style {
+            Style::Text => self.start,
+            Style::CodePoints => self.in_bytes.location(),
+        }
+    }
+}
+
+/// Writes bytes to the output stream as UTF-8 text
+///
+/// With the default [Style], valid UTF-8 sequences are written unchanged; with [CodePoints]
+/// style, the code point of every character is written instead (g.e. 'U+0041 U+00E9').
+/// Invalid sequences are written according to the provided [Invalid] representation: with the
+/// default one they are an error, as described for the [Reader]. A trailing incomplete sequence
+/// is only checked on [finish].
+///
+/// [CodePoints]: Style::CodePoints
+/// [finish]: crate::byte_writer::ByteWriter::finish
+pub struct Writer<W: Write> {
+    out_bytes: W,
+    style: Style,
+    invalid: Invalid,
+    formatter: Formatter,
+    decoder: Decoder,
+}
+
+impl<W: Write> Writer<W> {
+    pub fn new(out_bytes: W) -> Self {
+        Self::with_style(out_bytes, Style::default(), Invalid::default())
+    }
+
+    pub fn with_style(out_bytes: W, style: Style, invalid: Invalid) -> Self {
+        let format = Format {
+            separator: " ".to_string(),
+            ..Default::default()
+        };
+        Writer {
+            out_bytes,
+            style,
+            invalid,
+            formatter: Formatter::new(format),
+            decoder: Decoder::new(),
+        }
+    }
+
+    fn write_string(&mut self, string: &str) -> Result<(), OutError> {
+        match self.style {
+            Style::Text => util::write(&mut self.out_bytes, string.as_bytes(), string.len()),
+            Style::CodePoints => self.formatter.write(&mut self.out_bytes, string.as_bytes()),
+        }
+    }
+
+    fn write_char(&mut self, c: char) -> Result<(), OutError> {
+        match self.style {
+            Style::Text => self.write_string(c.encode_utf8(&mut [0u8; 4])),
+            Style::CodePoints => self.write_string(&format!("U+{:04X}", c as u32)),
+        }
+    }
+
+    /// Writes the units decoded so far
+    fn write_units(&mut self) -> Result<(), OutError> {
+        while let Some(unit) = self.decoder.next() {
+            match unit {
+                Unit::Char(c) => self.write_char(c)?,
+                Unit::Invalid(malformed, bytes) => match self.invalid {
+                    Invalid::Strict => return Err(malformed.error(|b| b)),
+                    Invalid::Replace => self.write_char(char::REPLACEMENT_CHARACTER)?,
+                    Invalid::Escape => {
+                        for b in bytes {
+                            self.write_string(&format!("\\x{b:02x}"))?;
+                        }
+                    }
+                },
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write> ByteWriter for Writer<W> {
+    fn write(&mut self, byte: u8) -> Result<(), OutError> {
+        self.decoder.push(byte);
+        self.write_units()
+    }
+
+    fn finish(&mut self) -> Result<(), OutError> {
+        self.decoder.end();
+        self.write_units()?;
+        match self.style {
+            Style::Text => util::flush(&mut self.out_bytes),
+            Style::CodePoints => self.formatter.finish(&mut self.out_bytes),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(input: &[u8]) -> Vec<Result<u8, InError>> {
+        Reader::new(input).collect()
+    }
+
+    fn encode(input: &[u8], style: Style, invalid: Invalid) -> Result<String, OutError> {
+        let mut output = vec![];
+        let mut writer = Writer::with_style(&mut output, style, invalid);
+        for b in input {
+            writer.write(*b)?;
+        }
+        writer.finish()?;
+        Ok(String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn read_valid() {
+        let input = "aé€😀".as_bytes();
+        let output: Vec<u8> = read(input).into_iter().map(|b| b.unwrap()).collect();
+        assert_eq!(input, output);
+    }
+
+    #[test]
+    fn read_invalid() {
+        let bytes = read(b"a\xc3(\xc0\xaf\xe0\x80\x80\xed\xa0\x80\xf4\x90\x80\x80\xff\x80");
+        assert!(matches!(
+            bytes[..],
+            [
+                Ok(b'a'),
+                Err(InError::Truncated {
+                    bytes: 1,
+                    expected: 2
+                }),
+                Ok(b'('),
+                Err(InError::Overlong),
+                Err(InError::Overlong),
+                Err(InError::InvalidByte('\u{80}')),
+                Err(InError::InvalidByte('\u{a0}')),
+                Err(InError::InvalidByte('\u{80}')),
+                Err(InError::OutOfRange),
+                Err(InError::InvalidByte('\u{80}')),
+                Err(InError::InvalidByte('\u{80}')),
+                Err(InError::InvalidByte('\u{ff}')),
+                Err(InError::InvalidByte('\u{80}')),
+            ]
+        ));
+        let mut reader = Reader::new(b"ab\xe2\x82".as_slice());
+        reader.next().unwrap().unwrap();
+        reader.next().unwrap().unwrap();
+        assert!(matches!(
+            reader.next(),
+            Some(Err(InError::ShortIO {
+                bytes: 2,
+                expected: 3
+            }))
+        ));
+        assert_eq!(2, reader.location().offset);
+        assert!(reader.next().is_none());
+        let mut reader = Reader::new(b"ab\xc3(x".as_slice());
+        reader.next().unwrap().unwrap();
+        reader.next().unwrap().unwrap();
+        assert!(matches!(
+            reader.next(),
+            Some(Err(InError::Truncated { .. }))
+        ));
+        assert_eq!(2, reader.location().offset);
+        assert!(matches!(reader.next(), Some(Ok(b'('))));
+        assert_eq!(3, reader.location().offset);
+    }
+
+    #[test]
+    fn read_code_points() {
+        let reader =
+            Reader::with_style("U+0041 u+00e9, U+1F600 \\xff".as_bytes(), Style::CodePoints);
+        let output: Vec<u8> = reader.map(|b| b.unwrap()).collect();
+        assert_eq!(b"A\xc3\xa9\xf0\x9f\x98\x80\xff".as_slice(), output);
+        for (input, invalid) in [
+            ("U+41", '1'),
+            ("U+D800", '0'),
+            ("0041", '0'),
+            ("U+00G1", 'G'),
+        ] {
+            let mut reader = Reader::with_style(input.as_bytes(), Style::CodePoints);
+            assert!(
+                matches!(reader.next(), Some(Err(InError::InvalidByte(c))) if c == invalid),
+                "{input}"
+            );
+        }
+        let mut reader = Reader::with_style("U+110000".as_bytes(), Style::CodePoints);
+        assert!(matches!(reader.next(), Some(Err(InError::OutOfRange))));
+    }
+
+    #[test]
+    fn write() {
+        let input = "aé€😀".as_bytes();
+        assert_eq!(
+            "aé€😀",
+            encode(input, Style::Text, Invalid::Strict).unwrap()
+        );
+        assert_eq!(
+            "U+0061 U+00E9 U+20AC U+1F600",
+            encode(input, Style::CodePoints, Invalid::Strict).unwrap()
+        );
+        let input = b"a\xc3(\xc0\xaf\xe2\x82";
+        assert_eq!(
+            "a\u{fffd}(\u{fffd}\u{fffd}",
+            encode(input, Style::Text, Invalid::Replace).unwrap()
+        );
+        assert_eq!(
+            "a\\xc3(\\xc0\\xaf\\xe2\\x82",
+            encode(input, Style::Text, Invalid::Escape).unwrap()
+        );
+        assert_eq!(
+            "U+0061 \\xc3 U+0028 \\xc0 \\xaf",
+            encode(&input[..5], Style::CodePoints, Invalid::Escape).unwrap()
+        );
+        assert!(matches!(
+            encode(input, Style::Text, Invalid::Strict),
+            Err(OutError::Truncated {
+                bytes: 1,
+                expected: 2
+            })
+        ));
+        assert!(matches!(
+            encode(b"\xe2\x82", Style::Text, Invalid::Strict),
+            Err(OutError::Truncated {
+                bytes: 2,
+                expected: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn roundtrip() {
+        let input = "aé€😀\u{10ffff}".as_bytes();
+        let output = encode(input, Style::CodePoints, Invalid::Escape).unwrap();
+        let reader = Reader::with_style(output.as_bytes(), Style::CodePoints);
+        assert_eq!(input, reader.map(|b| b.unwrap()).collect::<Vec<_>>());
+    }
+}
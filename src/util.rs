@@ -1,5 +1,5 @@
-use crate::error::OutError;
-use std::io::Write;
+use crate::error::{Location, OutError};
+use std::io::{Bytes, Read, Write};
 
 pub mod literals {
     pub const _0: u8 = b'0';
@@ -61,3 +61,93 @@ pub fn write<W: Write>(
         Err(e) => Err(OutError::StdIO(e)),
     }
 }
+
+pub fn flush<W: Write>(out_bytes: &mut W) -> Result<(), OutError> {
+    match out_bytes.flush() {
+        Ok(()) => Ok(()),
+        Err(e) => Err(OutError::StdIO(e)),
+    }
+}
+
+/// Input stream of bytes keeping track of the location of the last byte read
+pub struct Input<R: Read> {
+    in_bytes: Bytes<R>,
+    last: Location,
+    next: Location,
+}
+
+impl<R: Read> Input<R> {
+    // buffering is up to the caller (f.e. stdin is already buffered)
+    #[allow(clippy::unbuffered_bytes)]
+    pub fn new(read: R) -> Self {
+        Input {
+            in_bytes: read.bytes(),
+            last: Location::default(),
+            next: Location::default(),
+        }
+    }
+
+    pub fn location(&self) -> Location {
+        self.last
+    }
+
+    /// Reads next line, without the line terminator ('\n' or "\r\n")
+    pub fn line(&mut self) -> Option<std::io::Result<Vec<u8>>> {
+        let mut line = vec![];
+        loop {
+            match self.next() {
+                None if line.is_empty() => return None,
+                None | Some(Ok(b'\n')) => break,
+                Some(Ok(c)) => line.push(c),
+                Some(Err(e)) => return Some(Err(e)),
+            }
+        }
+        if line.last() == Some(&b'\r') {
+            line.pop();
+        }
+        Some(Ok(line))
+    }
+
+    /// Reads next token, that is a sequence of characters other than whitespace, ',', ';' and
+    /// brackets; location is left at the last character of the token
+    pub fn token(&mut self) -> Option<std::io::Result<Vec<u8>>> {
+        let mut token = vec![];
+        let mut end = self.last;
+        loop {
+            match self.next() {
+                None if token.is_empty() => return None,
+                None => break,
+                Some(Ok(c)) if c.is_ascii_whitespace() || b",;[]{}()".contains(&c) => {
+                    if !token.is_empty() {
+                        self.last = end;
+                        break;
+                    }
+                }
+                Some(Ok(c)) => {
+                    token.push(c);
+                    end = self.last;
+                }
+                Some(Err(e)) => return Some(Err(e)),
+            }
+        }
+        Some(Ok(token))
+    }
+}
+
+impl<R: Read> Iterator for Input<R> {
+    type Item = std::io::Result<u8>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let c = self.in_bytes.next()?;
+        if let Ok(c) = c {
+            self.last = self.next;
+            self.next.offset += 1;
+            if c == b'\n' {
+                self.next.line += 1;
+                self.next.column = 1;
+            } else {
+                self.next.column += 1;
+            }
+        }
+        Some(c)
+    }
+}
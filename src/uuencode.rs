@@ -0,0 +1,292 @@
+use crate::byte_reader::ByteReader;
+use crate::byte_writer::ByteWriter;
+use crate::error::{InError, Location, OutError};
+use crate::util;
+use std::collections::VecDeque;
+use std::io::{Read, Write};
+
+/// Maximum number of bytes coded by a line
+const LINE_BYTES: usize = 45;
+
+/// Symbols coding 6 bits values
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Variant {
+    /// uuencode: characters from '`' (0, also ' ' on input) and '!' (1) to '_' (63)
+    Uuencode,
+    /// xxencode: '+', '-', '0'-'9', 'A'-'Z' and 'a'-'z'
+    Xxencode,
+}
+
+impl Variant {
+    fn symbols(self) -> &'static [u8; 64] {
+        match self {
+            Variant::Uuencode => {
+                b"`!\"#$%&'()*+,-./0123456789:;<=>?@ABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]^_"
+            }
+            Variant::Xxencode => {
+                b"+-0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz"
+            }
+        }
+    }
+
+    fn valid(self, c: u8) -> Option<u8> {
+        match self {
+            Variant::Uuencode if (b' '..=b'`').contains(&c) => Some((c - b' ') & 0x3f),
+            Variant::Uuencode => None,
+            Variant::Xxencode => self.symbols().iter().position(|&s| s == c).map(|v| v as u8),
+        }
+    }
+}
+
+/// An iterator over Result<u8,[InError]>
+///
+/// Reads bytes from the input stream in uuencode or xxencode format (according to the provided
+/// [Variant]): lines preceding the 'begin' line (g.e. 'begin 644 data') are skipped, then every
+/// line codes up to 63 bytes, as many as the value of its first character, with 4 characters
+/// every 3 bytes; a line coding no bytes is followed by the 'end' line, after which any input is
+/// ignored.
+///
+/// A missing 'begin' line is reported as an invalid last line, as well as the end of input before
+/// the 'end' line.
+///
+/// [InError]: crate::error::InError
+pub struct Reader<R: Read> {
+    in_bytes: util::Input<R>,
+    variant: Variant,
+    line_number: usize,
+    begun: bool,
+    last: bool,
+    ended: bool,
+    bytes: VecDeque<u8>,
+}
+
+impl<R: Read> Reader<R> {
+    pub fn new(read: R, variant: Variant) -> Self {
+        Reader {
+            in_bytes: util::Input::new(read),
+            variant,
+            line_number: 0,
+            begun: false,
+            last: false,
+            ended: false,
+            bytes: VecDeque::new(),
+        }
+    }
+
+    /// Reads next line into self.bytes; returns false at the end of the encoded data
+    fn read_line(&mut self) -> Result<bool, InError> {
+        let line = match self.in_bytes.line() {
+            Some(line) => line.map_err(InError::StdIO)?,
+            None if self.line_number > 0 => {
+                self.ended = true;
+                return Err(InError::InvalidLine(self.line_number));
+            }
+            None => return Ok(false),
+        };
+        self.line_number += 1;
+        let line_number = self.line_number;
+        let invalid = || InError::InvalidLine(line_number);
+        if !self.begun {
+            self.begun = line.starts_with(b"begin ");
+            return Ok(true);
+        }
+        if self.last {
+            self.ended = true;
+            return match line.trim_ascii_end() {
+                b"end" => Ok(false),
+                _ => Err(invalid()),
+            };
+        }
+        let Some((&first, symbols)) = line.split_first() else {
+            return Err(invalid());
+        };
+        let length = self.variant.valid(first).ok_or_else(invalid)? as usize;
+        if length == 0 {
+            self.last = true;
+            return Ok(true);
+        }
+        let symbols = symbols.get(..length.div_ceil(3) * 4).ok_or_else(invalid)?;
+        let mut decoded = vec![];
+        for group in symbols.chunks(4) {
+            let mut value = 0u32;
+            for &c in group {
+                value = (value << 6) | self.variant.valid(c).ok_or_else(invalid)? as u32;
+            }
+            decoded.extend(&value.to_be_bytes()[1..]);
+        }
+        self.bytes.extend(&decoded[..length]);
+        Ok(true)
+    }
+}
+
+impl<R: Read> Iterator for Reader<R> {
+    type Item = Result<u8, InError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.bytes.is_empty() && !self.ended {
+            match self.read_line() {
+                Ok(true) => {}
+                Ok(false) => self.ended = true,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        Some(Ok(self.bytes.pop_front()?))
+    }
+}
+
+impl<R: Read> ByteReader for Reader<R> {
+    fn location(&self) -> Location {
+        self.in_bytes.location()
+    }
+}
+
+/// Writes bytes to the output stream in uuencode or xxencode format (according to the provided
+/// [Variant]), as a file with the provided name (g.e. 'begin 644 data'), 45 bytes per line
+///
+/// Since every line starts with the number of bytes it codes, the last line is only written on
+/// [finish].
+///
+/// [finish]: crate::byte_writer::ByteWriter::finish
+pub struct Writer<W: Write> {
+    out_bytes: W,
+    variant: Variant,
+    name: String,
+    begun: bool,
+    line: Vec<u8>,
+}
+
+impl<W: Write> Writer<W> {
+    pub fn new(out_bytes: W, variant: Variant) -> Self {
+        Self::with_name(out_bytes, variant, "data")
+    }
+
+    pub fn with_name(out_bytes: W, variant: Variant, name: &str) -> Self {
+        Writer {
+            out_bytes,
+            variant,
+            name: name.to_string(),
+            begun: false,
+            line: Vec::with_capacity(LINE_BYTES),
+        }
+    }
+
+    /// Writes self.line, preceded by the 'begin' line if first
+    fn write_line(&mut self) -> Result<(), OutError> {
+        let mut encoded = vec![];
+        if !self.begun {
+            self.begun = true;
+            encoded.extend(format!("begin 644 {}\n", self.name).as_bytes());
+        }
+        let symbols = self.variant.symbols();
+        encoded.push(symbols[self.line.len()]);
+        for group in self.line.chunks(3) {
+            let mut bytes = [0u8; 4];
+            bytes[1..1 + group.len()].copy_from_slice(group);
+            let value = u32::from_be_bytes(bytes);
+            for shift in [18, 12, 6, 0] {
+                encoded.push(symbols[(value >> shift) as usize & 0x3f]);
+            }
+        }
+        encoded.push(b'\n');
+        self.line.clear();
+        util::write(&mut self.out_bytes, &encoded, encoded.len())
+    }
+}
+
+impl<W: Write> ByteWriter for Writer<W> {
+    fn write(&mut self, byte: u8) -> Result<(), OutError> {
+        self.line.push(byte);
+        if self.line.len() == LINE_BYTES {
+            self.write_line()
+        } else {
+            Ok(())
+        }
+    }
+
+    fn finish(&mut self) -> Result<(), OutError> {
+        if !self.line.is_empty() {
+            self.write_line()?;
+        }
+        // line coding no bytes
+        self.write_line()?;
+        util::write(&mut self.out_bytes, b"end\n", 4)?;
+        util::flush(&mut self.out_bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(input: &[u8], variant: Variant) -> String {
+        let mut output = vec![];
+        let mut writer = Writer::new(&mut output, variant);
+        for b in input {
+            writer.write(*b).unwrap();
+        }
+        writer.finish().unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    fn decode(input: &str, variant: Variant) -> Vec<u8> {
+        Reader::new(input.as_bytes(), variant)
+            .map(|b| b.unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn read() {
+        let input = "From: x\n\nbegin 644 cat.txt\n#0V%T\n`\nend\nignored\n";
+        assert_eq!(b"Cat".as_slice(), decode(input, Variant::Uuencode));
+        let input = "begin 644 cat.txt\r\n#0V%T\r\n \r\nend\r\n";
+        assert_eq!(b"Cat".as_slice(), decode(input, Variant::Uuencode));
+        let input = "begin 644 cat.txt\n1Eq3o\n+\nend\n";
+        assert_eq!(b"Cat".as_slice(), decode(input, Variant::Xxencode));
+    }
+
+    #[test]
+    fn read_invalid() {
+        let input = "begin 644 x\n#0V%\n#0V~T\n`\nx\n";
+        let bytes: Vec<_> = Reader::new(input.as_bytes(), Variant::Uuencode).collect();
+        assert!(matches!(
+            bytes[..],
+            [
+                Err(InError::InvalidLine(2)),
+                Err(InError::InvalidLine(3)),
+                Err(InError::InvalidLine(5))
+            ]
+        ));
+        let bytes: Vec<_> = Reader::new("#0V%T\n".as_bytes(), Variant::Uuencode).collect();
+        assert!(matches!(bytes[..], [Err(InError::InvalidLine(1))]));
+        let input = "begin 644 x\n#0V%T\n";
+        let bytes: Vec<_> = Reader::new(input.as_bytes(), Variant::Uuencode).collect();
+        assert!(matches!(
+            bytes[..],
+            [Ok(b'C'), Ok(b'a'), Ok(b't'), Err(InError::InvalidLine(2))]
+        ));
+    }
+
+    #[test]
+    fn write() {
+        assert_eq!(
+            "begin 644 data\n#0V%T\n`\nend\n",
+            encode(b"Cat", Variant::Uuencode)
+        );
+        assert_eq!(
+            "begin 644 data\n1Eq3o\n+\nend\n",
+            encode(b"Cat", Variant::Xxencode)
+        );
+        assert_eq!("begin 644 data\n`\nend\n", encode(b"", Variant::Uuencode));
+        let encoded = encode(&[0; 46], Variant::Uuencode);
+        let lines: Vec<_> = encoded.lines().collect();
+        assert_eq!(format!("M{}", "`".repeat(60)), lines[1]);
+        assert_eq!("!````", lines[2]);
+    }
+
+    #[test]
+    fn roundtrip() {
+        let input: Vec<u8> = (0..=255).collect();
+        for variant in [Variant::Uuencode, Variant::Xxencode] {
+            assert_eq!(input, decode(&encode(&input, variant), variant));
+        }
+    }
+}